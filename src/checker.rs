@@ -0,0 +1,1159 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use ordermap::OrderMap;
+
+use crate::envirnment::{Param, Scope, Symbol};
+use crate::lexer::{self, Lexer};
+use crate::logger::Logger;
+use crate::parser::Parser;
+use crate::runtime::{canon, locate, primitives, Type};
+use crate::syntax::{Expr, Node};
+use crate::token::Token;
+use crate::utils::{Spanned, Wrapper};
+
+// a type of `None` is unknown, either because an error was already reported
+// for it or because it can only be known at runtime, and is never checked.
+type Kind = Option<Type>;
+
+fn numeric(kind: &Type) -> bool {
+  return matches!(kind, Type::Number | Type::Integer | Type::BigInt | Type::Decimal);
+}
+
+struct Frame {
+  label: String,
+  emits: Kind,
+}
+
+struct Pending {
+  name: Token,
+  args: OrderMap<String, Param>,
+  emits: Kind,
+  body: Node,
+}
+
+pub struct Checker {
+  scope: Scope,
+  logger: Box<Logger>,
+  errors: Vec<String>,
+  frames: Vec<Frame>,
+  yields: Vec<Kind>,
+  // the ifs used as values, within the current function, being checked.
+  branches: usize,
+  pending: Vec<Pending>,
+  loops: usize,
+  modules: HashMap<PathBuf, Scope>,
+  importing: Vec<PathBuf>,
+}
+
+impl Checker {
+  pub fn init(logger: Box<Logger>) -> Self {
+    let importing = vec![PathBuf::from(logger.filename())];
+
+    return Self {
+      scope: Scope::root(), logger, errors: vec![], frames: vec![], yields: vec![],
+      branches: 0, pending: vec![], loops: 0, modules: HashMap::new(), importing,
+    };
+  }
+  pub fn check(mut self, nodes: &[Node]) -> (Box<Logger>, Vec<String>) {
+    nodes.iter().for_each(|x| self.statement(x));
+    self.settle(0);
+
+    return (self.logger, self.errors);
+  }
+  // checks more code against everything declared so far. nothing declared by
+  // code with errors in it is kept.
+  pub fn extend(&mut self, logger: Box<Logger>, nodes: &[Node]) -> Vec<String> {
+    let scope = self.scope.clone();
+    self.importing = vec![PathBuf::from(logger.filename())];
+    self.logger = logger;
+
+    nodes.iter().for_each(|x| self.statement(x));
+    self.settle(0);
+
+    if !self.errors.is_empty() { self.scope = scope; }
+    return std::mem::take(&mut self.errors);
+  }
+  // what has been declared so far, to go back to if the code checked after
+  // fails when it is run.
+  pub fn snapshot(&self) -> Scope {
+    return self.scope.clone();
+  }
+  pub fn restore(&mut self, scope: Scope) {
+    self.scope = scope;
+  }
+  pub fn measure(&mut self, logger: Box<Logger>, expr: &Expr) -> Result<Kind, Vec<String>> {
+    self.logger = logger;
+    let kind = self.infer(expr);
+
+    if !self.errors.is_empty() {
+      return Err(std::mem::take(&mut self.errors));
+    }
+
+    return Ok(kind);
+  }
+
+  fn error<S:ToString, V:ToString, C:Spanned>(&mut self, header: S, message: V, spot: C) {
+    let error = self.logger.error(header, message, spot);
+    self.errors.push(error);
+  }
+
+  fn lookup<S:ToString>(&self, name: S) -> Option<&Symbol> {
+    return self.scope.get(name.to_string());
+  }
+  fn insert<S:ToString>(&mut self, name: S, symbol: Symbol) {
+    return self.scope.set(name, symbol);
+  }
+
+  // whether `name` is taken, reporting it if so. calls to builtins are never
+  // looked up, so nothing else may take their names.
+  fn defined(&mut self, name: &Token) -> bool {
+    if primitives().contains_key(&name.text) {
+      self.error("symbol already exists", format!("{:?} is a builtin function.", name.text), name);
+      return true;
+    }
+    if self.lookup(&name.text).is_some() {
+      self.error("symbol already exists", format!("{:?} has already been defined.", name.text), name);
+      return true;
+    }
+
+    return false;
+  }
+
+  fn enter(&mut self) -> usize {
+    let scope = std::mem::replace(&mut self.scope, Scope::root());
+    self.scope = Scope::init(scope);
+
+    return self.pending.len();
+  }
+  fn leave(&mut self, mark: usize) {
+    self.settle(mark);
+
+    let scope = std::mem::replace(&mut self.scope, Scope::root());
+    self.scope = scope.leave();
+  }
+  // named functions at the top level see the globals they are called with, so
+  // their bodies are checked once everything else has been declared.
+  fn settle(&mut self, mark: usize) {
+    while self.pending.len() > mark {
+      let Pending { name, args, emits, body } = self.pending.remove(mark);
+      self.function(&name.text, &name, &args, emits, &body);
+    }
+  }
+
+  fn module(&mut self, space: &[Token]) -> Option<Scope> {
+    let mut scope: Option<Scope> = None;
+
+    for part in space {
+      let symbol = match &scope {
+        Some(scope) => scope.get(&part.text),
+        None => self.lookup(&part.text),
+      };
+
+      scope = match symbol {
+        Some(Symbol::Module { scope }) => Some(scope.clone()),
+        Some(Symbol::Variable { kind: None, .. }) => return None,
+        Some(_) => {
+          self.error("invalid reference", format!("{:?} is not a module.", part.text), part);
+          return None;
+        },
+        None => {
+          self.error("symbol does not exist", format!("{:?} could not be resolved", part.text), part);
+          return None;
+        },
+      };
+    }
+
+    return scope;
+  }
+  fn resolve(&mut self, space: &[Token], name: &Token) -> Option<Symbol> {
+    let symbol = if space.is_empty() { self.lookup(&name.text).cloned() } else {
+      self.module(space)?.get(&name.text).cloned()
+    };
+
+    if symbol.is_none() {
+      self.error("symbol does not exist", format!("{:?} could not be resolved", name.text), name);
+    }
+
+    return symbol;
+  }
+}
+
+impl Checker {
+  fn kind(&mut self, expr: &Expr) -> Kind {
+    return match expr {
+      Expr::TypeRef { space, base, arrs, .. } => {
+        let mut parent = match self.resolve(space, base)? {
+          Symbol::TypeRefr { parent } => parent,
+          Symbol::Variable { kind: None, .. } => return None,
+          _ => {
+            self.error("invalid reference", format!("{:?} is not a type", base.text), base);
+            return None;
+          },
+        };
+
+        for _ in 0..*arrs {
+          parent = Type::Array(parent.wrap())
+        }
+
+        Some(parent)
+      },
+      Expr::FunType { args, kind, .. } => {
+        let args = args.iter().filter(|x| !matches!(x, Expr::NullVoid { .. })).map(|x| {
+          self.kind(x)
+        }).collect::<Vec<Kind>>();
+        let emits = self.kind(kind)?;
+
+        Some(Type::Function(args.into_iter().collect::<Option<_>>()?, emits.wrap()))
+      },
+      Expr::NullVoid { .. } => Some(Type::NullVoid),
+      _ => unreachable!(),
+    };
+  }
+  fn infer(&mut self, expr: &Expr) -> Kind {
+    let kind = match expr {
+      Expr::String { .. } => Type::String,
+      Expr::Number { value, .. } => lexer::numeric(&value.text),
+      Expr::Boolean { .. } => Type::Boolean,
+      Expr::NullVoid { .. } => Type::NullVoid,
+      Expr::Interpolated { parts, .. } => {
+        parts.iter().for_each(|x| { self.infer(x); });
+        Type::String
+      },
+      Expr::VarRef { value, .. } => {
+        let res = if let Some(sym) = self.lookup(&value.text) { sym.clone() } else {
+          self.error("symbol does not exist", format!("{:?} could not be resolved", value.text), value);
+          return None;
+        };
+
+        return self.value(res, value);
+      },
+      Expr::FunCall { name, args, .. } => {
+        if let Some(kind) = self.primitive(name, args) {
+          return kind;
+        }
+
+        let res = if let Some(sym) = self.lookup(&name.text) { sym.clone() } else {
+          self.error("symbol does not exist", format!("{:?} could not be resolved", name.text), name);
+          return None;
+        };
+
+        match res {
+          Symbol::Function { args: params, emits } => {
+            let params = params.into_iter().map(|(name, param)| (Some(name), param)).collect();
+            return self.call(&name.text, name, args, params, emits);
+          },
+          Symbol::Variable { kind: Some(Type::Function(params, emits)), .. } => {
+            return self.call(&name.text, name, args, unnamed(params), Some(*emits));
+          },
+          Symbol::Variable { kind: None, .. } => {
+            args.iter().for_each(|x| { self.infer(x); });
+            return None;
+          },
+          _ => {
+            self.error("invalid operation", format!("{:?} is not a function", &name.text), name);
+            return None;
+          },
+        }
+      },
+      Expr::Call { callee, args, .. } => {
+        match self.infer(callee) {
+          Some(Type::Function(params, emits)) => {
+            return self.call("lambda", &**callee, args, unnamed(params), Some(*emits));
+          },
+          Some(other) => {
+            self.error("invalid operation", format!("{other} is not a function"), &**callee);
+            return None;
+          },
+          None => {
+            args.iter().for_each(|x| { self.infer(x); });
+            return None;
+          },
+        }
+      },
+      Expr::Object { attrs, .. } => {
+        let mut fields = HashMap::new();
+        let mut known = true;
+
+        for attr in attrs {
+          match attr {
+            Expr::ObjectField { name, attr, .. } => match self.infer(attr) {
+              Some(kind) => { fields.insert(name.text.clone(), kind); },
+              None => known = false,
+            },
+            Expr::NullVoid { .. } => (),
+            other => {
+              self.error("invalid expression", "expected `name = value` in object.", other);
+              known = false;
+            },
+          }
+        }
+
+        if !known { return None }
+        Type::Object(fields)
+      },
+      Expr::Construct { space, name, attrs, .. } => {
+        let fields = match self.resolve(space, name)? {
+          Symbol::TypeRefr { parent: Type::Struct(_, fields) } => fields,
+          Symbol::Variable { kind: None, .. } => return None,
+          _ => {
+            self.error("invalid reference", format!("{:?} is not a struct type.", name.text), name);
+            return None;
+          },
+        };
+
+        let mut given: Vec<&str> = vec![];
+
+        for attr in attrs {
+          let (field, attr) = match attr {
+            Expr::ObjectField { name, attr, .. } => (name, attr),
+            _ => continue,
+          };
+
+          let value = self.infer(attr);
+
+          let kind = if let Some(kind) = fields.get(&field.text) { kind } else {
+            self.error("invalid field", format!("{:?} is not a field of {}.", field.text, name.text), field);
+            continue;
+          };
+
+          if given.contains(&field.text.as_str()) {
+            self.error("invalid field", format!("{:?} was given more than once.", field.text), field);
+            continue;
+          }
+
+          if let Some(value) = value.filter(|x| x != kind) {
+            self.error("mismatched types", format!("{}:{} expected {kind}, but was given {value}.", name.text, field.text), &**attr);
+          }
+
+          given.push(&field.text);
+        }
+
+        let missing = fields.keys().filter(|x| !given.contains(&x.as_str())).map(|x| {
+          format!("{x:?}")
+        }).collect::<Vec<String>>();
+
+        if !missing.is_empty() {
+          self.error("missing fields", format!("{} is missing {}.", name.text, missing.join(", ")), expr);
+        }
+
+        Type::Struct(name.text.clone(), fields)
+      },
+      Expr::ObjectField { attr, .. } => return self.infer(attr),
+      Expr::Array { value, .. } => {
+        let mut first: Kind = None;
+
+        for (i, item) in value.iter().enumerate() {
+          let kind = self.infer(item);
+
+          match (&first, kind) {
+            (Some(first), Some(kind)) if &kind != first => {
+              self.error("mismatched types", format!("found {kind} in {first}[]."), item);
+            },
+            (None, kind) if i == 0 => first = kind,
+            _ => (),
+          }
+        }
+
+        Type::Array(first?.wrap())
+      },
+      Expr::Index { parent, index, .. } => {
+        let from = self.infer(parent);
+
+        if let Some(kind) = self.infer(index).filter(|x| x != &Type::Integer) {
+          self.error("invalid expression", format!("cannot perform index with {kind}."), &**index);
+        }
+
+        match from? {
+          Type::String => Type::String,
+          Type::Array(kind) => *kind,
+          other => {
+            self.error("invalid operation", format!("cannot perform index upon {other}"), expr);
+            return None;
+          },
+        }
+      },
+      Expr::Attribute { parent, attr, .. } => {
+        let name = if let Expr::VarRef { value, .. } = &**attr
+          { value } else { unreachable!() };
+
+        if let Some(space) = parent.path().filter(|x| {
+          matches!(self.lookup(&x[0].text), Some(Symbol::Module { .. }))
+        }) {
+          let scope = self.module(&space)?;
+
+          return match scope.get(&name.text) {
+            Some(symbol) => self.value(symbol.clone(), name),
+            None => {
+              self.error("symbol does not exist", format!("{:?} could not be resolved in {:?}", name.text, space.last().unwrap().text), name);
+              None
+            },
+          };
+        }
+
+        match self.infer(parent)? {
+          Type::Object(attrs) if attrs.contains_key(&name.text) => attrs[&name.text].clone(),
+          Type::Struct(_, attrs) if attrs.contains_key(&name.text) => attrs[&name.text].clone(),
+          parent @ (Type::Object(_) | Type::Struct(..)) => {
+            self.error("invalid attribute", format!("{:?} is not a field of {parent}.", name.text), name);
+            return None;
+          },
+          other => {
+            self.error("invalid operation", format!("cannot access attribute {:?} upon {other}.", name.text), name);
+            return None;
+          },
+        }
+      },
+      Expr::Lambda { args, kind, body, .. } => {
+        let (args, emits) = self.signature(args, kind);
+        self.function("lambda", expr, &args, emits.clone(), body);
+
+        return function(&args, emits);
+      },
+      Expr::If { cond, body, other, .. } => {
+        self.truthy(cond);
+
+        let outer = std::mem::take(&mut self.yields);
+        let mut kinds = vec![];
+        self.branches += 1;
+
+        for branch in [body, other] {
+          self.statement(branch);
+          kinds.append(&mut self.yields);
+
+          if !emits(branch) { kinds.push(Some(Type::NullVoid)) }
+        }
+
+        self.branches -= 1;
+        self.yields = outer;
+
+        let first = kinds.first()?.clone()?;
+        if kinds.iter().any(|x| x.as_ref() != Some(&first)) { return None }
+        first
+      },
+      Expr::Unary { oper, value, .. } => {
+        let v = self.infer(value);
+        let o = oper.text.as_str();
+        let expects = if o == "!" { Type::Boolean } else { Type::Number };
+
+        // any other number keeps its type through `-` and `+`.
+        if o != "!" && v.as_ref().is_some_and(numeric) { return v; }
+
+        if let Some(v) = v.filter(|x| x != &expects) {
+          self.error("mismatched types", format!("unary {o:?} expects {expects}, but was given {v}."), &**value);
+        }
+
+        expects
+      },
+      Expr::BoolOper { lhs, oper, rhs, .. } => {
+        let l = self.infer(lhs);
+        let r = self.infer(rhs);
+        let o = oper.text.as_str();
+
+        if o != "==" && o != "!=" {
+          let comparable = |x: &Kind| matches!(x, None | Some(Type::String | Type::Array(_))) || x.as_ref().is_some_and(numeric);
+
+          if !comparable(&l) || !comparable(&r) {
+            self.error("invalid operation", format!("{o:?} is a numeric exclusive comparison operator."), oper);
+          }
+        }
+
+        Type::Boolean
+      },
+      Expr::MathOper { lhs, oper, rhs, .. } => {
+        let l = self.infer(lhs);
+        let r = self.infer(rhs);
+        let (l, o) = (l?, oper.text.as_str());
+
+        if !matches!(l, Type::String | Type::Array(_)) && !numeric(&l) {
+          self.error("invalid operation", format!("cannot perform {o:?} upon a {l}"), &**lhs);
+          return None;
+        }
+
+        if o == "+" {
+          let r = if let Some(r) = r { r } else {
+            return if let Type::Array(_) = l { None } else { Some(l) };
+          };
+
+          return match (&l, &r) {
+            (Type::String, _) => Some(l),
+            (l, r) if numeric(l) && l == r => Some(l.clone()),
+            (Type::Array(_), Type::Array(_)) if l == r => Some(l),
+            (Type::Array(kind), _) if **kind == r => Some(l),
+            _ => {
+              self.error("invalid operation", format!("cannot perform {o:?} upon a {l} with a {r}."), &**lhs);
+              None
+            },
+          };
+        }
+
+        if !numeric(&l) || r.as_ref().is_some_and(|x| !numeric(x)) {
+          self.error("invalid operation", format!("{o:?} is an exclusive numeric operation."), &**lhs);
+          return None;
+        }
+
+        if let Some(r) = r.filter(|x| x != &l) {
+          self.error("invalid operation", format!("cannot perform {o:?} upon a {l} with a {r}."), &**lhs);
+          return None;
+        }
+
+        l
+      },
+      Expr::Chained { lhs, rhs, .. } => {
+        let l = self.infer(lhs);
+        let r = self.infer(rhs);
+
+        if [l, r].iter().any(|x| x.as_ref().is_some_and(|x| x != &Type::Boolean)) {
+          self.error("invalid operation", "cannot chain non-boolean values.", expr);
+        }
+
+        Type::Boolean
+      },
+      Expr::TypeRef { .. } | Expr::FunType { .. } => return self.kind(expr),
+      Expr::TypePair { .. } => return None,
+    };
+
+    return Some(kind);
+  }
+  fn value(&mut self, symbol: Symbol, name: &Token) -> Kind {
+    return match symbol {
+      Symbol::Variable { kind, .. } => kind,
+      Symbol::Function { args, emits } => function(&args, emits),
+      Symbol::TypeRefr { .. } => {
+        self.error("invalid reference", format!("{:?} is a type, not a value.", name.text), name);
+        None
+      },
+      Symbol::Module { .. } => {
+        self.error("invalid reference", format!("{:?} is a module, not a value.", name.text), name);
+        None
+      },
+    };
+  }
+  fn primitive(&mut self, name: &Token, args: &[Expr]) -> Option<Kind> {
+    if !primitives().contains_key(&name.text) {
+      return None;
+    }
+
+    let args = args.iter().filter(|x| !matches!(x, Expr::NullVoid { .. })).collect::<Vec<&Expr>>();
+    let kinds = args.iter().map(|x| self.infer(x)).collect::<Vec<Kind>>();
+
+    let kind = match name.text.as_str() {
+      "disp" => Some(Type::NullVoid),
+      "input" => Some(Type::String),
+      "format" => {
+        match kinds.first() {
+          None => self.error("invalid arguments", "format takes in a string", name),
+          Some(Some(kind)) if kind != &Type::String => {
+            self.error("invalid arguments", "format takes in a string", args[0])
+          },
+          _ => (),
+        }
+
+        Some(Type::String)
+      },
+      "typeof" => {
+        if kinds.is_empty() {
+          self.error("invalid arguments", "typeof takes in at least one value", name);
+        }
+
+        kinds.first().cloned().flatten().map(|x| Type::Array(x.wrap()))
+      },
+      "args" => Some(Type::Array(Type::String.wrap())),
+      "env" => {
+        match kinds.first() {
+          None => self.error("invalid arguments", "env takes in a string", name),
+          Some(Some(kind)) if kind != &Type::String => {
+            self.error("invalid arguments", "env takes in a string", args[0])
+          },
+          _ => (),
+        }
+
+        Some(Type::String)
+      },
+      "exit" => {
+        match kinds.first() {
+          None => self.error("invalid arguments", "exit takes in an int", name),
+          Some(Some(kind)) if kind != &Type::Integer => {
+            self.error("invalid arguments", "exit takes in an int", args[0])
+          },
+          _ => (),
+        }
+
+        Some(Type::NullVoid)
+      },
+      "int" | "num" | "bigint" | "decimal" => {
+        let message = format!("{} takes in a numeric type or str", name.text);
+
+        match kinds.first() {
+          None => self.error("invalid arguments", message, name),
+          Some(Some(kind)) if kind != &Type::String && !numeric(kind) => {
+            self.error("invalid arguments", message, args[0])
+          },
+          _ => (),
+        }
+
+        match name.text.as_str() {
+          "int" => Some(Type::Integer),
+          "num" => Some(Type::Number),
+          "bigint" => Some(Type::BigInt),
+          _ => Some(Type::Decimal),
+        }
+      },
+      // a builtin without a signature here is only checked when it is run.
+      _ => None,
+    };
+
+    return Some(kind);
+  }
+  fn call<C:Spanned + Copy>(&mut self, label: &str, spot: C, args: &[Expr], params: Vec<(Option<String>, Param)>, emits: Kind) -> Kind {
+    let args = args.iter().filter(|arg| {
+      !matches!(arg, Expr::NullVoid { .. })
+    }).collect::<Vec<&Expr>>();
+
+    let given = args.iter().filter(|arg| !matches!(arg, Expr::ObjectField { .. })).count();
+
+    if given > params.len() {
+      self.error("arguments differ in length", format!("{label:?} expected at most {} args, but was given {given}.", params.len()), spot);
+    }
+
+    let mut bound: Vec<usize> = vec![];
+    let mut named = false;
+
+    for (i, arg) in args.into_iter().enumerate() {
+      let value = self.infer(arg);
+
+      let slot = match arg {
+        Expr::ObjectField { name, .. } => {
+          named = true;
+
+          if params.iter().any(|(name, _)| name.is_none()) {
+            self.error("invalid argument", format!("{label:?} is a function value, so its parameters have no names."), name);
+            continue;
+          }
+          let slot = if let Some(slot) = params.iter().position(|x| x.0.as_ref() == Some(&name.text)) { slot } else {
+            self.error("invalid argument", format!("{label:?} has no parameter named {:?}.", name.text), name);
+            continue;
+          };
+
+          if bound.contains(&slot) {
+            self.error("invalid argument", format!("{:?} was given more than once.", name.text), name);
+            continue;
+          }
+
+          slot
+        },
+        arg => {
+          if named {
+            self.error("invalid argument", "positional arguments cannot follow named arguments.", arg);
+            continue;
+          }
+          if i >= params.len() { continue }
+
+          i
+        },
+      };
+
+      bound.push(slot);
+      let (name, param) = &params[slot];
+
+      if let (Some(kind), Some(value)) = (&param.kind, value) {
+        if kind != &value {
+          let name = name.clone().map(|x| format!("{x:?}")).unwrap_or(format!("argument {}", slot + 1));
+          self.error("mismatched types", format!("{label:?} expected {kind} for {name}, but was given {value}."), arg);
+        }
+      }
+    }
+
+    for (slot, (name, param)) in params.iter().enumerate() {
+      if let Some(name) = name.as_ref().filter(|_| !param.optional && !bound.contains(&slot)) {
+        self.error("missing argument", format!("{label:?} expected a value for {name:?}."), spot);
+      }
+    }
+
+    return emits;
+  }
+  fn truthy(&mut self, cond: &Expr) {
+    let kind = self.infer(cond);
+
+    if let Some(kind) = kind.filter(|x| !(matches!(x, Type::String | Type::Boolean | Type::NullVoid) || numeric(x))) {
+      self.error("invalid expression", format!("{kind} cannot be evaluated to a boolean."), cond);
+    }
+  }
+  fn signature(&mut self, args: &[Expr], kind: &Expr) -> (OrderMap<String, Param>, Kind) {
+    let mut params = OrderMap::new();
+
+    for expr in args {
+      let (name, kind, default) = match expr {
+        Expr::TypePair { name, kind, default, .. } => (name, kind, default),
+        Expr::NullVoid { .. } => continue,
+        other => {
+          self.error("invalid argument", "expected `name: type` for parameter.", other);
+          continue;
+        },
+      };
+
+      let kind = self.kind(kind);
+
+      if let Some(default) = default {
+        let value = self.infer(default);
+
+        if let (Some(kind), Some(value)) = (&kind, value) {
+          if kind != &value {
+            self.error("mismatched types", format!("{:?} is {kind}, but its default is {value}.", name.text), &**default);
+          }
+        }
+      }
+
+      if params.contains_key(&name.text) {
+        self.error("symbol already exists", format!("{:?} is already a parameter.", name.text), name);
+        continue;
+      }
+
+      params.insert(name.text.clone(), Param { kind, optional: default.is_some() });
+    }
+
+    return (params, self.kind(kind));
+  }
+  fn function<C:Spanned>(&mut self, label: &str, spot: C, args: &OrderMap<String, Param>, emits: Kind, body: &Node) {
+    self.frames.push(Frame { label: label.to_string(), emits: emits.clone() });
+    let branches = std::mem::take(&mut self.branches);
+    let loops = std::mem::take(&mut self.loops);
+    let mark = self.enter();
+
+    for (name, param) in args {
+      self.insert(name, Symbol::var(param.kind.clone(), true));
+    }
+
+    self.statement(body);
+
+    self.leave(mark);
+    self.loops = loops;
+    self.branches = branches;
+    self.frames.pop();
+
+    if let Some(emits) = emits.filter(|x| x != &Type::NullVoid && !self::emits(body)) {
+      self.error("missing emission", format!("{label:?} does not emit {emits} on every path."), spot);
+    }
+  }
+}
+
+impl Checker {
+  fn statement(&mut self, node: &Node) {
+    match node {
+      Node::SetAssign { name, value, .. } => self.assign(name, value, false),
+      Node::VarAssign { name, value, .. } => self.assign(name, value, true),
+      Node::ChangeVal { target, value, .. } => self.modify(target, value),
+      Node::ImportLib { path, .. } => self.import(path),
+      Node::EmitValue { value, .. } => self.emit(value),
+      Node::DeclareType { name, attrs, .. } => self.create_type(name, attrs),
+      Node::WhileLoop { cond, body, .. } => {
+        self.truthy(cond);
+        self.body(None, body);
+      },
+      Node::ForLoop { item, iter, body, .. } => {
+        let kind = match self.infer(iter) {
+          Some(Type::Array(kind)) => Some(*kind),
+          Some(Type::String | Type::Object(_) | Type::Struct(..)) => Some(Type::String),
+          Some(other) => {
+            self.error("invalid operation", format!("cannot iterate over {other}."), iter);
+            None
+          },
+          None => None,
+        };
+
+        self.body(Some((item, kind)), body);
+      },
+      Node::Break { token, .. } | Node::Continue { token, .. } => {
+        if self.loops == 0 {
+          self.error("invalid operation", format!("{:?} can only be used inside of a loop.", token.text), token);
+        }
+      },
+      Node::Compound { value, .. } => value.iter().for_each(|x| self.statement(x)),
+      // an if standing as a statement lets the `emit`s in it through.
+      Node::Expression { expr: Expr::If { cond, body, other, .. }, .. } => {
+        self.truthy(cond);
+        self.statement(body);
+        self.statement(other);
+      },
+      Node::Expression { expr, .. } => { self.infer(expr); },
+    }
+  }
+
+  fn assign(&mut self, name: &Token, value: &Expr, mutable: bool) {
+    if let Expr::Lambda { args, kind, body, .. } = value {
+      return self.fundef(name, args, kind, body);
+    }
+
+    let kind = self.infer(value);
+
+    if self.defined(name) { return }
+
+    self.insert(&name.text, Symbol::var(kind, mutable));
+  }
+  fn fundef(&mut self, name: &Token, args: &[Expr], kind: &Expr, body: &Node) {
+    if self.defined(name) { return }
+
+    let (args, emits) = self.signature(args, kind);
+
+    self.insert(&name.text, Symbol::Function { args: args.clone(), emits: emits.clone() });
+
+    // anywhere else they capture what is declared before them, and nothing after.
+    if !self.frames.is_empty() || self.loops != 0 {
+      return self.function(&name.text, name, &args, emits, body);
+    }
+
+    self.pending.push(Pending { name: name.clone(), args, emits, body: body.clone() });
+  }
+  fn modify(&mut self, target: &Expr, value: &Expr) {
+    let value = self.infer(value);
+    let mut root = target;
+
+    let name = loop {
+      root = match root {
+        Expr::Attribute { parent, .. } => parent,
+        Expr::Index { parent, .. } => parent,
+        Expr::VarRef { value, .. } => break value,
+        _ => unreachable!()
+      };
+    };
+
+    let symbol = if let Some(res) = self.lookup(&name.text) { res.clone() } else {
+      return self.error("symbol does not exist", format!("{:?} has could not be resolved.", name.text), name);
+    };
+
+    match symbol {
+      Symbol::Variable { mutable: false, .. } => {
+        return self.error("invalid operation", format!("{:?} is a constant and cannot be reassigned.", name.text), name);
+      },
+      Symbol::Variable { .. } => (),
+      Symbol::Function { .. } => {
+        return self.error("invalid operation", format!("{:?} is a function which cannot be assigned to a value.", name.text), name);
+      },
+      Symbol::TypeRefr { .. } => {
+        return self.error("invalid operation", format!("{:?} is a type reference which cannot be assigned to a value.", name.text), name);
+      },
+      Symbol::Module { .. } => {
+        return self.error("invalid operation", format!("{:?} is a module which cannot be assigned to a value.", name.text), name);
+      },
+    };
+
+    if let (Some(slot), Some(value)) = (self.infer(target), value) {
+      if slot != value {
+        self.error("invalid operation", format!("{:?} has been assigned to be {slot}, not {value}", label(target)), target);
+      }
+    }
+  }
+  fn import(&mut self, path: &[Token]) {
+    let name = path.last().unwrap();
+    let scope = self.load(path);
+
+    if self.defined(name) { return }
+
+    match scope {
+      Some(scope) => self.insert(&name.text, Symbol::Module { scope }),
+      None => self.insert(&name.text, Symbol::var(None, false)),
+    }
+  }
+  fn load(&mut self, path: &[Token]) -> Option<Scope> {
+    let file = match locate(self.importing.last().unwrap(), path) {
+      Ok(file) => file,
+      Err(path_s) => {
+        self.error("invalid path", format!("{path_s} is not a valid filepath."), path);
+        return None;
+      },
+    };
+
+    let key = canon(&file);
+
+    if let Some(start) = self.importing.iter().position(|x| canon(x) == key) {
+      let chain = self.importing[start..].iter().chain([&file]).map(|x| {
+        x.display().to_string()
+      }).collect::<Vec<String>>().join(" -> ");
+
+      self.error("import cycle", chain, path);
+      return None;
+    }
+
+    if let Some(scope) = self.modules.get(&key) {
+      return Some(scope.clone());
+    }
+
+    let source = match std::fs::read_to_string(&file) {
+      Ok(source) => source,
+      Err(err) => {
+        self.error("invalid path", format!("{} could not be read: {err}", file.display()), path);
+        return None;
+      },
+    };
+
+    let logger = Logger::new(file.display().to_string(), source);
+    let (nodes, logger, errors) = Parser::init(Lexer::new(logger)).parse();
+
+    if !errors.is_empty() {
+      self.errors.extend(errors);
+      return None;
+    }
+
+    let scope = std::mem::replace(&mut self.scope, Scope::root());
+    let logger = std::mem::replace(&mut self.logger, logger);
+    let frames = std::mem::take(&mut self.frames);
+    let yields = std::mem::take(&mut self.yields);
+    let branches = std::mem::take(&mut self.branches);
+    let loops = std::mem::take(&mut self.loops);
+    let mark = self.pending.len();
+    self.importing.push(file);
+
+    nodes.iter().for_each(|x| self.statement(x));
+    self.settle(mark);
+
+    self.importing.pop();
+    self.logger = logger;
+    self.frames = frames;
+    self.yields = yields;
+    self.branches = branches;
+    self.loops = loops;
+
+    let module = std::mem::replace(&mut self.scope, scope);
+    self.modules.insert(key, module.clone());
+
+    return Some(module);
+  }
+  fn emit(&mut self, value: &Expr) {
+    let kind = self.infer(value);
+
+    if self.branches != 0 {
+      return self.yields.push(kind);
+    }
+
+    let frame = if let Some(frame) = self.frames.last() { frame } else {
+      return self.error("invalid operation", "\"emit\" can only be used inside of a function, or an if used as a value.", value);
+    };
+
+    if let (Some(emits), Some(kind)) = (&frame.emits, kind) {
+      if emits != &kind {
+        let label = frame.label.clone();
+        self.error("mismatched types", format!("{label:?} expected to emit {emits}, but emits {kind}."), value);
+      }
+    }
+  }
+  fn create_type(&mut self, name: &Token, attrs: &[Expr]) {
+    let mut fields = OrderMap::new();
+    let mut known = true;
+
+    for expr in attrs {
+      if let Expr::TypePair { name, kind, .. } = expr {
+        match self.kind(kind) {
+          Some(kind) => { fields.insert(name.text.clone(), kind); },
+          None => known = false,
+        }
+      }
+    }
+
+    if self.defined(name) { return }
+
+    if !known {
+      return self.insert(&name.text, Symbol::var(None, false));
+    }
+
+    self.insert(&name.text, Symbol::refr(Type::Struct(name.text.clone(), fields)));
+  }
+  fn body(&mut self, item: Option<(&Token, Kind)>, body: &Node) {
+    self.loops += 1;
+    let mark = self.enter();
+
+    if let Some((item, kind)) = item {
+      self.insert(&item.text, Symbol::var(kind, false));
+    }
+
+    self.statement(body);
+
+    self.leave(mark);
+    self.loops -= 1;
+  }
+}
+
+fn unnamed(params: Vec<Type>) -> Vec<(Option<String>, Param)> {
+  return params.into_iter().map(|kind| {
+    (None, Param { kind: Some(kind), optional: true })
+  }).collect();
+}
+
+fn function(args: &OrderMap<String, Param>, emits: Kind) -> Kind {
+  let args = args.values().map(|x| x.kind.clone()).collect::<Option<Vec<Type>>>()?;
+  return Some(Type::Function(args, emits?.wrap()));
+}
+
+// whether every path through `node` reaches an `emit`.
+fn emits(node: &Node) -> bool {
+  let branches = |expr: &Expr| {
+    if let Expr::If { body, other, .. } = expr
+      { emits(body) && emits(other) } else { false }
+  };
+
+  return match node {
+    Node::EmitValue { .. } => true,
+    Node::Compound { value, .. } => value.iter().any(emits),
+    Node::Expression { expr, .. } => branches(expr),
+    _ => false,
+  };
+}
+
+fn label(target: &Expr) -> String {
+  return match target {
+    Expr::VarRef { value, .. } => value.text.clone(),
+    Expr::Attribute { parent, attr, .. } => format!("{}:{}", label(parent), label(attr)),
+    Expr::Index { parent, .. } => format!("{}[]", label(parent)),
+    _ => unreachable!(),
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn check(source: &str) -> Vec<String> {
+    let (nodes, logger, errors) = Parser::init(Lexer::new(Logger::new("test.baf".into(), source.into()))).parse();
+    assert!(errors.is_empty(), "{errors:?}");
+
+    return Checker::init(logger).check(&nodes).1;
+  }
+
+  #[test]
+  fn every_type_error_is_collected() {
+    let errors = check(&[
+      "disp(nope);",
+      "set f = {a: int} -> str { emit a; };",
+      "f(\"x\");",
+      "f(1, 2);",
+      "set c = 1; c = 2;",
+      "set g = {a: int} -> int { if a > 0 { emit 1; }; };",
+      "1 + \"s\";",
+    ].join("\n"));
+
+    let expected = [
+      "\"nope\" could not be resolved",
+      "\"f\" expected to emit str, but emits int.",
+      "\"f\" expected int for \"a\", but was given str.",
+      "\"f\" expected at most 1 args, but was given 2.",
+      "\"c\" is a constant and cannot be reassigned.",
+      "\"g\" does not emit int on every path.",
+      "cannot perform \"+\" upon a int with a str.",
+    ];
+
+    assert_eq!(errors.len(), expected.len(), "{errors:#?}");
+
+    for message in expected {
+      assert!(errors.iter().any(|x| x.contains(message)), "{message}");
+    }
+  }
+
+  #[test]
+  fn functions_are_checked_by_their_type() {
+    let source = "set apply = {f: (int) -> int, x: int} -> int { emit f(x); };";
+
+    assert!(check(&format!("{source} apply({{n: int}} -> int {{ emit n; }}, 1);")).is_empty());
+
+    let errors = check(&format!("{source} apply({{n: str}} -> int {{ emit 1; }}, 1);"));
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("mismatched types"), "{}", errors[0]);
+  }
+
+  #[test]
+  fn an_if_used_as_a_value_does_not_emit_for_the_function() {
+    let errors = check("set f = {c: bool} -> int { set v = if c { emit 1; } else { emit 2; }; };");
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("does not emit int on every path"));
+    assert!(check("set f = {c: bool} -> int { if c { emit 1; } else { emit 2; }; };").is_empty());
+  }
+
+  #[test]
+  fn local_functions_only_see_what_is_declared_before_them() {
+    let errors = check("set outer = {} -> int { set inner = {} -> int { emit later; }; set later = 5; emit inner(); };");
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("\"later\" could not be resolved"));
+    assert!(check("set f = {} -> int { emit g(); }; set g = {} -> int { emit 1; };").is_empty());
+  }
+
+  #[test]
+  fn builtins_cannot_be_declared_over() {
+    for name in ["args", "env", "exit", "disp"] {
+      let errors = check(&format!("set {name} = {{code: int}} -> int {{ emit code; }};"));
+
+      assert_eq!(errors.len(), 1, "{name}");
+      assert!(errors[0].contains("is a builtin function"));
+    }
+  }
+
+  #[test]
+  fn attributes_are_checked_against_their_object() {
+    let source = "var o = {a = {b = 1}};";
+
+    assert!(check(&format!("{source} o:a:b = 2;")).is_empty());
+    assert!(check(&format!("{source} o:a:q;"))[0].contains("\"q\" is not a field of"));
+    assert!(check(&format!("{source} o:a:b = \"s\";"))[0].contains("has been assigned to be int, not str"));
+  }
+
+  #[test]
+  fn structs_are_told_apart_by_name() {
+    let source = "type Point { x: int, y: int }; type Pair { x: int, y: int }; set f = {p: Point} -> int { emit p:x; };";
+
+    assert!(check(&format!("{source} f(Point {{ x = 1, y = 2 }});")).is_empty());
+    assert!(check(&format!("{source} f(Pair {{ x = 1, y = 2 }});"))[0].contains("mismatched types"));
+    assert!(check(&format!("{source} f({{x = 1, y = 2}});"))[0].contains("mismatched types"));
+    assert!(check(&format!("{source} Point {{ x = 1 }};"))[0].contains("Point is missing \"y\"."));
+  }
+
+  #[test]
+  fn break_and_continue_need_a_loop() {
+    assert_eq!(check("break;").len(), 1);
+    assert_eq!(check("set f = {} { continue; }; while true { f(); }").len(), 1);
+    assert!(check("while true { break; }").is_empty());
+  }
+
+  #[test]
+  fn typeof_needs_a_value() {
+    let errors = check("set f = {x: int} -> int { emit x; };\ndisp(f(typeof()));");
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("typeof takes in at least one value"));
+    assert!(check("disp(typeof(1, \"a\"));").is_empty());
+  }
+
+  #[test]
+  fn interpolated_expressions_are_checked() {
+    assert!(check("set n = 1; \"n is {n + 1}\";").is_empty());
+
+    let errors = check("\"{nope} and {1 + true}\";");
+    assert_eq!(errors.len(), 2, "{errors:?}");
+  }
+
+  #[test]
+  fn function_values_take_no_named_arguments() {
+    let errors = check("set apply = {f: (int) -> int} -> int { emit f(x = 1); };");
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("have no names"));
+
+    assert!(check("set f = {x: int} -> int { emit x; }; f(x = 1);").is_empty());
+  }
+
+  #[test]
+  fn emit_needs_a_function_or_an_if_used_as_a_value() {
+    for source in ["emit 5;", "while true { emit 1; }", "for x in [1] { if true { emit x; }; }"] {
+      let errors = check(source);
+
+      assert_eq!(errors.len(), 1, "{source}: {errors:?}");
+      assert!(errors[0].contains("can only be used inside of a function"), "{source}");
+    }
+
+    assert!(check("set v = if true { emit 1; } else { emit 2; };").is_empty());
+  }
+
+  #[test]
+  fn every_builtin_is_known_to_the_checker() {
+    for name in primitives().keys() {
+      let errors = check(&format!("set {name} = 1;"));
+      assert!(errors.iter().any(|x| x.contains("is a builtin function")), "{name}: {errors:?}");
+    }
+  }
+}
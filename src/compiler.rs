@@ -0,0 +1,676 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::decimal::Decimal;
+use crate::lexer::numeric;
+use crate::logger::Logger;
+use crate::runtime::{primitives, PrimeFunc, Scope, Type, Value};
+use crate::syntax::{Expr, Node};
+use crate::token::{Span, Token};
+use crate::utils::Spanned;
+
+pub type Site = Span;
+
+#[derive(Clone, PartialEq)]
+pub enum Place {
+  Local(usize),
+  Capture(usize),
+  Global(usize),
+  Own,
+}
+
+pub enum Bind {
+  Variable(bool),
+  Function,
+  TypeRefr,
+}
+
+pub enum Step {
+  Field(String, Site),
+  Index(Site),
+}
+
+pub struct Arg {
+  pub name: String,
+  pub default: Option<Site>,
+}
+
+pub struct Proto {
+  pub args: Vec<Arg>,
+  pub captures: Vec<Place>,
+  pub slots: usize,
+  pub code: Vec<Action>,
+  pub logger: Rc<Logger>,
+}
+
+pub enum Action {
+  Push(Value),
+  Pop,
+  Jump(usize),
+  JumpIfNot { target: usize, spot: Site },
+  Mark,
+  Unmark,
+  Exit { depth: usize, leave: bool, carry: bool, target: usize },
+  Clear { from: usize, to: usize },
+
+  Load { place: Place, name: String, spot: Site, callee: bool },
+  Define { place: Place, outer: Option<Place>, bind: Bind, name: String, spot: Site },
+  Store { place: Place, name: String, steps: Vec<Step>, spot: Site, root: Site },
+  Path { place: Place, path: Vec<(String, Site)> },
+  Import { path: Vec<Token>, place: Place, outer: Option<Place> },
+
+  Array { spots: Vec<Site> },
+  Object { names: Vec<String> },
+  Construct { place: Place, space: Vec<(String, Site)>, name: (String, Site), fields: Vec<(String, Site, Site)>, spot: Site },
+  Index { spot: Site, index: Site },
+  Attr { name: String, spot: Site },
+
+  Unary { oper: String, spot: Site },
+  Compare { oper: String, spot: Site },
+  Math { oper: String, spot: Site },
+  Chain { oper: String, spot: Site },
+  Interpolate { count: usize },
+
+  TypeRef { place: Place, space: Vec<(String, Site)>, name: (String, Site), arrs: usize },
+  FunType { args: usize },
+  Struct { name: String, fields: Vec<String> },
+  Closure { proto: Rc<Proto> },
+
+  Call { label: String, names: Vec<Option<(String, Site)>>, spots: Vec<Site>, spot: Site },
+  Native { func: PrimeFunc, count: usize, spots: Vec<Site> },
+  Return,
+
+  Items { spot: Site },
+  Next { target: usize },
+  Raise { header: String, message: String, spot: Site },
+}
+
+// a loop that `break` and `continue` may escape, or an if used as a value
+// that `emit` may.
+struct Region {
+  looped: bool,
+  start: usize,
+  exits: Vec<usize>,
+}
+
+struct Context {
+  own: Option<String>,
+  code: Vec<Action>,
+  blocks: Vec<HashMap<String, usize>>,
+  next: usize,
+  slots: usize,
+  captures: Vec<(String, Place)>,
+  regions: Vec<Region>,
+}
+
+impl Context {
+  fn init(own: Option<&str>) -> Self {
+    return Context {
+      own: own.map(|x| x.to_string()), code: vec![], blocks: vec![],
+      next: 0, slots: 0, captures: vec![], regions: vec![],
+    };
+  }
+}
+
+pub struct Compiler {
+  logger: Rc<Logger>,
+  globals: Rc<RefCell<Scope>>,
+  contexts: Vec<Context>,
+}
+
+fn sites(tokens: &[Token]) -> Vec<(String, Site)> {
+  return tokens.iter().map(|x| (x.text.clone(), x.span())).collect();
+}
+
+impl Compiler {
+  pub fn init(logger: Rc<Logger>, globals: Rc<RefCell<Scope>>) -> Self {
+    return Compiler { logger, globals, contexts: vec![] };
+  }
+  pub fn compile(mut self, nodes: &[Node]) -> Rc<Proto> {
+    self.contexts.push(Context::init(None));
+    nodes.iter().for_each(|x| self.statement(x));
+
+    return self.finish(vec![]);
+  }
+  // like `compile`, but the script returns the value of a trailing expression.
+  pub fn interactive(mut self, nodes: &[Node]) -> Rc<Proto> {
+    self.contexts.push(Context::init(None));
+
+    let (last, rest) = if let Some(split) = nodes.split_last() { split } else {
+      return self.finish(vec![]);
+    };
+
+    rest.iter().for_each(|x| self.statement(x));
+
+    if let Node::Expression { expr, .. } = last {
+      self.expr(expr);
+      self.push(Action::Return);
+    } else { self.statement(last); }
+
+    return self.finish(vec![]);
+  }
+  fn finish(&mut self, args: Vec<Arg>) -> Rc<Proto> {
+    self.push(Action::Push(Value::NullVoid));
+    self.push(Action::Return);
+
+    let context = self.contexts.pop().unwrap();
+    let captures = context.captures.into_iter().map(|x| x.1).collect();
+
+    return Rc::new(Proto {
+      args, captures, slots: context.slots, code: context.code, logger: self.logger.clone(),
+    });
+  }
+
+  fn context(&mut self) -> &mut Context {
+    return self.contexts.last_mut().unwrap();
+  }
+  fn push(&mut self, action: Action) -> usize {
+    let code = &mut self.context().code;
+    code.push(action);
+
+    return code.len() - 1;
+  }
+  fn here(&mut self) -> usize {
+    return self.context().code.len();
+  }
+  fn patch(&mut self, at: usize, to: usize) {
+    match &mut self.context().code[at] {
+      Action::Jump(target) |
+      Action::JumpIfNot { target, .. } |
+      Action::Exit { target, .. } |
+      Action::Next { target } => *target = to,
+      Action::Clear { to: end, .. } => *end = to,
+      _ => unreachable!(),
+    }
+  }
+  fn raise<S:ToString, V:ToString, C:Spanned>(&mut self, header: S, message: V, spot: C) {
+    self.push(Action::Raise { header: header.to_string(), message: message.to_string(), spot: spot.span() });
+  }
+
+  fn enter(&mut self) -> usize {
+    let context = self.context();
+    context.blocks.push(HashMap::new());
+
+    return context.next;
+  }
+  fn leave(&mut self, from: usize) {
+    let context = self.context();
+    context.blocks.pop();
+    context.next = from;
+  }
+  fn slot(&mut self, name: &str) -> usize {
+    let context = self.context();
+    let slot = context.next;
+
+    context.next += 1;
+    context.slots = context.slots.max(context.next);
+    context.blocks.last_mut().unwrap().insert(name.to_string(), slot);
+
+    return slot;
+  }
+  // the place a new symbol is written to, and the place it would shadow.
+  fn declare(&mut self, name: &str) -> (Place, Option<Place>) {
+    let context = self.context();
+
+    let block = if let Some(block) = context.blocks.last() { block } else {
+      return (self.global(name), None);
+    };
+
+    if let Some(slot) = block.get(name) {
+      return (Place::Local(*slot), None);
+    }
+
+    let outer = self.resolve(name);
+    return (Place::Local(self.slot(name)), Some(outer));
+  }
+  fn resolve(&mut self, name: &str) -> Place {
+    let level = self.contexts.len() - 1;
+    return self.lookup(level, name).unwrap_or_else(|| self.global(name));
+  }
+  fn global(&self, name: &str) -> Place {
+    return Place::Global(self.globals.borrow_mut().slot(name));
+  }
+  fn lookup(&mut self, level: usize, name: &str) -> Option<Place> {
+    let context = &self.contexts[level];
+
+    for block in context.blocks.iter().rev() {
+      if let Some(slot) = block.get(name) { return Some(Place::Local(*slot)) }
+    }
+
+    if context.own.as_deref() == Some(name) {
+      return Some(Place::Own);
+    }
+    if let Some(i) = context.captures.iter().position(|x| x.0 == name) {
+      return Some(Place::Capture(i));
+    }
+    if level == 0 { return None }
+
+    let outer = self.lookup(level - 1, name)?;
+    let context = &mut self.contexts[level];
+    context.captures.push((name.to_string(), outer));
+
+    return Some(Place::Capture(context.captures.len() - 1));
+  }
+}
+
+impl Compiler {
+  fn statement(&mut self, node: &Node) {
+    match node {
+      Node::SetAssign { name, value, .. } => self.assign(name, value, false),
+      Node::VarAssign { name, value, .. } => self.assign(name, value, true),
+      Node::ChangeVal { target, value, .. } => self.modify(target, value),
+      Node::ImportLib { path, .. } => {
+        let (place, outer) = self.declare(&path.last().unwrap().text);
+        self.push(Action::Import { path: path.clone(), place, outer });
+      },
+      Node::EmitValue { value, .. } => self.emit(value),
+      Node::DeclareType { name, attrs, .. } => {
+        let mut fields = vec![];
+
+        for attr in attrs {
+          if let Expr::TypePair { name, kind, .. } = attr {
+            self.typeref(kind);
+            fields.push(name.text.clone());
+          }
+        }
+
+        self.push(Action::Struct { name: name.text.clone(), fields });
+        self.define(name, Bind::TypeRefr);
+      },
+      Node::WhileLoop { cond, body, .. } => self.while_loop(cond, body),
+      Node::ForLoop { item, iter, body, .. } => self.for_loop(item, iter, body),
+      Node::Break { token, .. } => self.jump(token, true),
+      Node::Continue { token, .. } => self.jump(token, false),
+      Node::Compound { value, .. } => value.iter().for_each(|x| self.statement(x)),
+      Node::Expression { expr: Expr::If { cond, body, other, .. }, .. } => {
+        self.conditional(cond, body, other, false);
+        self.push(Action::Pop);
+      },
+      Node::Expression { expr, .. } => {
+        self.expr(expr);
+        self.push(Action::Pop);
+      },
+    }
+  }
+
+  fn define(&mut self, name: &Token, bind: Bind) {
+    let (place, outer) = self.declare(&name.text);
+    self.push(Action::Define { place, outer, bind, name: name.text.clone(), spot: name.span() });
+  }
+  fn assign(&mut self, name: &Token, value: &Expr, mutable: bool) {
+    if let Expr::Lambda { args, kind, body, .. } = value {
+      self.function(Some(&name.text), args, kind, body);
+      return self.define(name, Bind::Function);
+    }
+
+    self.expr(value);
+    self.define(name, Bind::Variable(mutable));
+  }
+  fn modify(&mut self, target: &Expr, value: &Expr) {
+    self.expr(value);
+
+    let mut steps = vec![];
+    let mut indexes = vec![];
+    let mut root = target;
+
+    let name = loop {
+      root = match root {
+        Expr::Attribute { parent, attr, .. } => {
+          let name = if let Expr::VarRef { value, .. } = &**attr
+            { value } else { unreachable!() };
+
+          steps.push(Step::Field(name.text.clone(), name.span()));
+          parent
+        },
+        Expr::Index { parent, index, .. } => {
+          steps.push(Step::Index(index.span()));
+          indexes.push(&**index);
+          parent
+        },
+        Expr::VarRef { value, .. } => break value,
+        _ => unreachable!()
+      };
+    };
+
+    steps.reverse();
+    indexes.into_iter().rev().for_each(|x| self.expr(x));
+
+    let place = self.resolve(&name.text);
+    self.push(Action::Store { place, name: name.text.clone(), steps, spot: target.span(), root: name.span() });
+  }
+  // an `emit` gives its value to the nearest if used as a value, as in
+  // `set y = if c { emit 1; } else { emit 2; };`. any other `emit` leaves the
+  // function, and outside of one is an error.
+  fn emit(&mut self, value: &Expr) {
+    let regions = &self.context().regions;
+    let depth = regions.len();
+
+    let index = match regions.iter().rposition(|x| !x.looped) {
+      Some(index) => index,
+      None if self.contexts.len() > 1 => {
+        self.expr(value);
+        self.push(Action::Return);
+        return;
+      },
+      None => {
+        return self.raise("invalid operation", "\"emit\" can only be used inside of a function, or an if used as a value.", value);
+      },
+    };
+
+    self.expr(value);
+    let at = self.push(Action::Exit { depth: depth - 1 - index, leave: true, carry: true, target: 0 });
+    self.context().regions[index].exits.push(at);
+  }
+  fn jump(&mut self, token: &Token, leave: bool) {
+    let regions = &self.context().regions;
+    let depth = regions.len();
+
+    let index = if let Some(index) = regions.iter().rposition(|x| x.looped) { index } else {
+      return self.raise("invalid operation", format!("{:?} can only be used inside of a loop.", token.text), token);
+    };
+
+    let target = if leave { 0 } else { regions[index].start };
+    let at = self.push(Action::Exit { depth: depth - 1 - index, leave, carry: false, target });
+
+    if leave {
+      self.context().regions[index].exits.push(at);
+    }
+  }
+  fn settle(&mut self) {
+    let region = self.context().regions.pop().unwrap();
+    let after = self.here();
+
+    region.exits.into_iter().for_each(|x| self.patch(x, after));
+  }
+  fn while_loop(&mut self, cond: &Expr, body: &Node) {
+    self.push(Action::Mark);
+
+    let start = self.here();
+    let from = self.enter();
+    let clear = self.push(Action::Clear { from, to: 0 });
+
+    self.expr(cond);
+    let check = self.push(Action::JumpIfNot { target: 0, spot: cond.span() });
+
+    self.context().regions.push(Region { looped: true, start, exits: vec![] });
+    self.statement(body);
+    self.push(Action::Jump(start));
+
+    let end = self.here();
+    self.patch(check, end);
+    self.push(Action::Unmark);
+    self.settle();
+
+    let slots = self.context().slots;
+    self.patch(clear, slots);
+    self.leave(from);
+  }
+  fn for_loop(&mut self, item: &Token, iter: &Expr, body: &Node) {
+    self.expr(iter);
+    self.push(Action::Items { spot: iter.span() });
+    self.push(Action::Mark);
+
+    let start = self.here();
+    let from = self.enter();
+    let clear = self.push(Action::Clear { from, to: 0 });
+    let next = self.push(Action::Next { target: 0 });
+
+    let place = Place::Local(self.slot(&item.text));
+    self.push(Action::Define { place, outer: None, bind: Bind::Variable(false), name: item.text.clone(), spot: item.span() });
+
+    self.context().regions.push(Region { looped: true, start, exits: vec![] });
+    self.statement(body);
+    self.push(Action::Jump(start));
+
+    let end = self.here();
+    self.patch(next, end);
+    self.push(Action::Unmark);
+    self.settle();
+
+    self.push(Action::Pop);
+    self.push(Action::Pop);
+
+    let slots = self.context().slots;
+    self.patch(clear, slots);
+    self.leave(from);
+  }
+}
+
+impl Compiler {
+  fn expr(&mut self, expr: &Expr) {
+    match expr {
+      Expr::String { value, .. } => { self.push(Action::Push(Value::String(value.text.clone()))); },
+      Expr::Interpolated { parts, .. } => {
+        parts.iter().for_each(|x| self.expr(x));
+        self.push(Action::Interpolate { count: parts.len() });
+      },
+      Expr::Number { value, .. } => {
+        // the lexer only lets through literals that parse as their type.
+        let text = value.text.trim_end_matches(['n', 'd']);
+
+        let value = match numeric(&value.text) {
+          Type::BigInt => Value::BigInt(text.parse().unwrap()),
+          Type::Decimal => Value::Decimal(Decimal::parse(text).unwrap()),
+          Type::Integer => Value::Integer(text.parse().unwrap()),
+          _ => Value::Number(text.parse().unwrap()),
+        };
+
+        self.push(Action::Push(value));
+      },
+      Expr::Boolean { value, .. } => { self.push(Action::Push(Value::Boolean(value.text.parse().unwrap()))); },
+      Expr::VarRef { value, .. } => {
+        let place = self.resolve(&value.text);
+        self.push(Action::Load { place, name: value.text.clone(), spot: value.span(), callee: false });
+      },
+      Expr::FunCall { name, args, .. } => {
+        let args = args.iter().filter(|x| !matches!(x, Expr::NullVoid { .. })).collect::<Vec<&Expr>>();
+
+        if let Some(func) = primitives().get(&name.text) {
+          args.iter().for_each(|x| self.expr(x));
+
+          let mut spots = args.iter().map(|x| x.span()).collect::<Vec<Site>>();
+          if spots.is_empty() { spots.push(name.span()) }
+
+          self.push(Action::Native { func: *func, count: args.len(), spots });
+          return;
+        }
+
+        let place = self.resolve(&name.text);
+        self.push(Action::Load { place, name: name.text.clone(), spot: name.span(), callee: true });
+        self.call(&name.text, name.span(), &args);
+      },
+      Expr::Call { callee, args, .. } => {
+        let args = args.iter().filter(|x| !matches!(x, Expr::NullVoid { .. })).collect::<Vec<&Expr>>();
+
+        self.expr(callee);
+        self.call("lambda", callee.span(), &args);
+      },
+      Expr::Object { attrs, .. } => {
+        let mut names = vec![];
+
+        for attr in attrs {
+          match attr {
+            Expr::ObjectField { name, attr, .. } => {
+              self.expr(attr);
+              names.push(name.text.clone());
+            },
+            Expr::NullVoid { .. } => break,
+            other => self.raise("invalid expression", "expected `name = value` in object.", other),
+          }
+        }
+
+        self.push(Action::Object { names });
+      },
+      Expr::Construct { space, name, attrs, .. } => {
+        let mut fields = vec![];
+
+        for attr in attrs {
+          if let Expr::ObjectField { name, attr, .. } = attr {
+            self.expr(attr);
+            fields.push((name.text.clone(), name.span(), attr.span()));
+          }
+        }
+
+        let place = self.resolve(&space.first().unwrap_or(name).text);
+        self.push(Action::Construct {
+          place, space: sites(space), name: (name.text.clone(), name.span()), fields, spot: expr.span()
+        });
+      },
+      Expr::ObjectField { attr, .. } => self.expr(attr),
+      Expr::Array { value, .. } => {
+        value.iter().for_each(|x| self.expr(x));
+        self.push(Action::Array { spots: value.iter().map(|x| x.span()).collect() });
+      },
+      Expr::Index { parent, index, .. } => {
+        self.expr(parent);
+        self.expr(index);
+        self.push(Action::Index { spot: expr.span(), index: index.span() });
+      },
+      Expr::Attribute { parent, attr, .. } => {
+        let name = if let Expr::VarRef { value, .. } = &**attr
+          { value } else { unreachable!() };
+
+        if let Some(mut path) = parent.path() {
+          path.push(name.clone());
+
+          let place = self.resolve(&path[0].text);
+          self.push(Action::Path { place, path: sites(&path) });
+          return;
+        }
+
+        self.expr(parent);
+        self.push(Action::Attr { name: name.text.clone(), spot: name.span() });
+      },
+      Expr::Lambda { args, kind, body, .. } => self.function(None, args, kind, body),
+      Expr::If { cond, body, other, .. } => self.conditional(cond, body, other, true),
+      Expr::Unary { oper, value, .. } => {
+        self.expr(value);
+        self.push(Action::Unary { oper: oper.text.clone(), spot: value.span() });
+      },
+      Expr::BoolOper { lhs, oper, rhs, .. } => {
+        self.expr(lhs);
+        self.expr(rhs);
+        self.push(Action::Compare { oper: oper.text.clone(), spot: oper.span() });
+      },
+      Expr::MathOper { lhs, oper, rhs, .. } => {
+        self.expr(lhs);
+        self.expr(rhs);
+        self.push(Action::Math { oper: oper.text.clone(), spot: lhs.span() });
+      },
+      Expr::Chained { lhs, stich, rhs, .. } => {
+        self.expr(lhs);
+        self.expr(rhs);
+        self.push(Action::Chain { oper: stich.text.clone(), spot: expr.span() });
+      },
+      Expr::TypeRef { .. } | Expr::FunType { .. } => self.typeref(expr),
+      Expr::TypePair { .. } | Expr::NullVoid { .. } => { self.push(Action::Push(Value::NullVoid)); },
+    }
+  }
+  // only an if used as a value catches the `emit`s in its branches, one
+  // standing as a statement lets them through.
+  fn conditional(&mut self, cond: &Expr, body: &Node, other: &Node, value: bool) {
+    if value {
+      self.push(Action::Mark);
+      self.context().regions.push(Region { looped: false, start: 0, exits: vec![] });
+    }
+
+    self.expr(cond);
+    let check = self.push(Action::JumpIfNot { target: 0, spot: cond.span() });
+
+    self.branch(body);
+    let skip = self.push(Action::Jump(0));
+
+    let here = self.here();
+    self.patch(check, here);
+    self.branch(other);
+
+    let here = self.here();
+    self.patch(skip, here);
+
+    if value {
+      self.push(Action::Unmark);
+      self.settle();
+    }
+  }
+  fn branch(&mut self, node: &Node) {
+    if let Node::Expression { expr, .. } = node {
+      return self.expr(expr);
+    }
+
+    self.statement(node);
+    self.push(Action::Push(Value::NullVoid));
+  }
+  fn call(&mut self, label: &str, spot: Site, args: &[&Expr]) {
+    let mut names = vec![];
+
+    for arg in args {
+      match arg {
+        Expr::ObjectField { name, attr, .. } => {
+          self.expr(attr);
+          names.push(Some((name.text.clone(), name.span())));
+        },
+        other => {
+          self.expr(other);
+          names.push(None);
+        },
+      }
+    }
+
+    let spots = args.iter().map(|x| x.span()).collect();
+    self.push(Action::Call { label: label.to_string(), names, spots, spot });
+  }
+  fn typeref(&mut self, expr: &Expr) {
+    match expr {
+      Expr::TypeRef { space, base, arrs, .. } => {
+        let place = self.resolve(&space.first().unwrap_or(base).text);
+        self.push(Action::TypeRef { place, space: sites(space), name: (base.text.clone(), base.span()), arrs: *arrs });
+      },
+      Expr::FunType { args, kind, .. } => {
+        let args = args.iter().filter(|x| !matches!(x, Expr::NullVoid { .. })).collect::<Vec<&Expr>>();
+
+        args.iter().for_each(|x| self.typeref(x));
+        self.typeref(kind);
+        self.push(Action::FunType { args: args.len() });
+      },
+      Expr::NullVoid { .. } => { self.push(Action::Push(Value::TypeRef(Type::NullVoid))); },
+      other => self.expr(other),
+    }
+  }
+  fn function(&mut self, own: Option<&str>, args: &[Expr], kind: &Expr, body: &Node) {
+    let mut params: Vec<Arg> = vec![];
+
+    for arg in args {
+      let (name, kind, default) = if let Expr::TypePair { name, kind, default, .. } = arg
+        { (name, kind, default) } else { continue };
+
+      if params.iter().any(|x| x.name == name.text) {
+        return self.raise("symbol already exists", format!("{:?} is already a parameter.", name.text), name);
+      }
+
+      self.typeref(kind);
+
+      if let Some(default) = default {
+        self.expr(default);
+      }
+
+      params.push(Arg { name: name.text.clone(), default: default.as_ref().map(|x| x.span()) });
+    }
+
+    self.typeref(kind);
+    self.contexts.push(Context::init(own));
+    self.enter();
+
+    for param in &params {
+      self.slot(&param.name);
+    }
+
+    match body {
+      Node::Compound { value, .. } => value.iter().for_each(|x| self.statement(x)),
+      other => self.statement(other),
+    }
+
+    let proto = self.finish(params);
+    self.push(Action::Closure { proto });
+  }
+}
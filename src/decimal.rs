@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use num_bigint::{BigInt, Sign};
+use serde::Serialize;
+
+// a fixed-point number, `units` counted in steps of 10 to the `-scale`, so
+// `12.50` is 1250 units at a scale of 2.
+#[derive(Clone, Serialize)]
+pub struct Decimal {
+  units: BigInt,
+  scale: u32,
+}
+
+// the largest exponent a decimal may be written with, as each step of one
+// costs a digit.
+const LIMIT: i64 = 4096;
+// the fewest digits a quotient keeps past the point.
+const PLACES: u32 = 16;
+
+fn power(scale: u32) -> BigInt {
+  return BigInt::from(10).pow(scale);
+}
+
+impl Decimal {
+  pub fn new(units: BigInt, scale: u32) -> Self {
+    return Decimal { units, scale };
+  }
+  // reads plain decimal text such as `-12.50` or `1.5e3`, or none if it is
+  // malformed or its exponent is past the limit.
+  pub fn parse(text: &str) -> Option<Self> {
+    let (mantissa, exponent) = text.split_once(['e', 'E']).unwrap_or((text, "0"));
+    let exponent: i64 = exponent.parse().ok().filter(|x: &i64| x.abs() <= LIMIT)?;
+
+    let (whole, fract) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if fract.starts_with(['+', '-']) || whole.trim_start_matches(['+', '-']).len() + fract.len() == 0 {
+      return None;
+    }
+
+    let units: BigInt = format!("{whole}{fract}").parse().ok()?;
+    let scale = fract.len() as i64 - exponent;
+
+    if scale < 0 {
+      return Some(Decimal::new(units * power(u32::try_from(-scale).ok()?), 0));
+    }
+
+    return Some(Decimal::new(units, u32::try_from(scale).ok()?));
+  }
+
+  // the units of both at the larger of their scales.
+  fn align(&self, other: &Decimal) -> (BigInt, BigInt, u32) {
+    let scale = self.scale.max(other.scale);
+
+    let l = &self.units * power(scale - self.scale);
+    let r = &other.units * power(scale - other.scale);
+
+    return (l, r, scale);
+  }
+
+  pub fn add(&self, other: &Decimal) -> Decimal {
+    let (l, r, scale) = self.align(other);
+    return Decimal::new(l + r, scale);
+  }
+  pub fn sub(&self, other: &Decimal) -> Decimal {
+    let (l, r, scale) = self.align(other);
+    return Decimal::new(l - r, scale);
+  }
+  pub fn mul(&self, other: &Decimal) -> Decimal {
+    return Decimal::new(&self.units * &other.units, self.scale + other.scale);
+  }
+  // keeps at least `PLACES` digits, dropping any past them, then trims the
+  // zeros it ends in back down to the larger scale of the two.
+  pub fn div(&self, other: &Decimal) -> Option<Decimal> {
+    let (l, r, scale) = self.align(other);
+    if r.sign() == Sign::NoSign { return None }
+
+    let places = scale.max(PLACES);
+    return Some(Decimal::new(l * power(places) / r, places).trim(scale));
+  }
+  pub fn rem(&self, other: &Decimal) -> Option<Decimal> {
+    let (l, r, scale) = self.align(other);
+    if r.sign() == Sign::NoSign { return None }
+
+    return Some(Decimal::new(l % r, scale));
+  }
+  pub fn neg(&self) -> Decimal {
+    return Decimal::new(-&self.units, self.scale);
+  }
+  pub fn is_negative(&self) -> bool {
+    return self.units.sign() == Sign::Minus;
+  }
+  fn trim(mut self, scale: u32) -> Decimal {
+    let ten = BigInt::from(10);
+
+    while self.scale > scale && (&self.units % &ten).sign() == Sign::NoSign {
+      self.units /= &ten;
+      self.scale -= 1;
+    }
+
+    return self;
+  }
+  // the whole part, rounded towards zero.
+  pub fn trunc(&self) -> BigInt {
+    return &self.units / power(self.scale);
+  }
+}
+
+impl From<BigInt> for Decimal {
+  fn from(value: BigInt) -> Self {
+    return Decimal::new(value, 0);
+  }
+}
+
+impl PartialEq for Decimal {
+  fn eq(&self, other: &Self) -> bool {
+    return self.cmp(other) == Ordering::Equal;
+  }
+}
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    return Some(self.cmp(other));
+  }
+}
+impl Ord for Decimal {
+  fn cmp(&self, other: &Self) -> Ordering {
+    let (l, r, _) = self.align(other);
+    return l.cmp(&r);
+  }
+}
+
+impl Display for Decimal {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let digits = self.units.magnitude().to_string();
+    let sign = if self.is_negative() { "-" } else { "" };
+
+    if self.scale == 0 {
+      return write!(f, "{sign}{digits}");
+    }
+
+    // pads `5` at a scale of 2 out to `0.05`.
+    let scale = self.scale as usize;
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (whole, fract) = digits.split_at(digits.len() - scale);
+
+    write!(f, "{sign}{whole}.{fract}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn quotient(l: &str, r: &str) -> String {
+    return Decimal::parse(l).unwrap().div(&Decimal::parse(r).unwrap()).unwrap().to_string();
+  }
+
+  #[test]
+  fn division_keeps_a_fraction() {
+    assert_eq!(quotient("1", "3"), "0.3333333333333333");
+    assert_eq!(quotient("-2", "3"), "-0.6666666666666666");
+    assert_eq!(quotient("1", "4"), "0.25");
+    assert_eq!(quotient("6", "3"), "2");
+    assert_eq!(quotient("1.50", "3"), "0.50");
+    assert!(Decimal::parse("1").unwrap().div(&Decimal::parse("0.0").unwrap()).is_none());
+  }
+
+  #[test]
+  fn exponents_past_the_limit_are_refused() {
+    assert!(Decimal::parse("1e4096").is_some());
+    assert!(Decimal::parse("1e4097").is_none());
+    assert!(Decimal::parse("1e-99999999999").is_none());
+  }
+
+  #[test]
+  fn text_is_read_and_shown_exactly() {
+    let shown = |text: &str| Decimal::parse(text).map(|x| x.to_string());
+
+    assert_eq!(shown("12.50"), Some("12.50".into()));
+    assert_eq!(shown("-0.05"), Some("-0.05".into()));
+    assert_eq!(shown("1.5e3"), Some("1500".into()));
+    assert_eq!(shown("15e-3"), Some("0.015".into()));
+    assert_eq!(shown("."), None);
+    assert_eq!(shown("1.-5"), None);
+  }
+
+  #[test]
+  fn arithmetic_keeps_every_digit() {
+    let (a, b) = (Decimal::parse("0.1").unwrap(), Decimal::parse("0.2").unwrap());
+
+    assert_eq!(a.add(&b).to_string(), "0.3");
+    assert_eq!(a.sub(&b).to_string(), "-0.1");
+    assert_eq!(a.mul(&b).to_string(), "0.02");
+    assert_eq!(Decimal::parse("5.5").unwrap().rem(&Decimal::parse("2").unwrap()).unwrap().to_string(), "1.5");
+    assert!(a.add(&b) == Decimal::parse("0.300").unwrap());
+  }
+}
@@ -1,152 +1,390 @@
-use crate::{logger::Logger, token::{Class, Token}, utils::Wrapper};
+use num_bigint::BigInt;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::{decimal::Decimal, logger::Logger, runtime::Type, token::{Class, Span, Token}, utils::Wrapper};
 
 pub struct Lexer {
   tokens: Vec<Token>,
+  errors: Vec<String>,
+  templates: Vec<usize>,
+  docs: Vec<String>,
   logger: Box<Logger>,
   source: Vec<char>,
   coords: [usize; 2],
   pointer: usize,
+  // the byte offset of `pointer` in the source.
+  offset: usize,
+}
+
+fn radix(raw: &str) -> u32 {
+  return match raw.get(..2) {
+    Some("0x" | "0X") => 16,
+    Some("0o" | "0O") => 8,
+    Some("0b" | "0B") => 2,
+    _ => 10,
+  };
+}
+// the magnitude of the least int, one past the largest, so it is only let
+// through for the parser to fold into a negated literal.
+pub const LEAST: &str = "9223372036854775808";
+
+// normalizes a number literal to the decimal text `parse` accepts, keeping an
+// `n` suffix for bigints and a `d` for decimals, or none if it is malformed.
+// underscores may only sit between two digits, and literals without a dot,
+// exponent or suffix are ints, so they must fit in one.
+fn numeral(raw: &str) -> Option<String> {
+  let radix = radix(raw);
+  let digits = if radix == 10 { raw } else { &raw[2..] };
+
+  // `d` is a hex digit, so only base ten literals can be decimals.
+  let (digits, suffix) = match digits.strip_suffix('n') {
+    Some(digits) => (digits, "n"),
+    None => match digits.strip_suffix('d').filter(|_| radix == 10) {
+      Some(digits) => (digits, "d"),
+      None => (digits, ""),
+    },
+  };
+
+  let chars = digits.chars().collect::<Vec<char>>();
+
+  for (i, char) in chars.iter().enumerate() {
+    if *char != '_' { continue; }
+
+    let before = i > 0 && chars[i - 1].is_digit(radix);
+    let after = chars.get(i + 1).is_some_and(|x| x.is_digit(radix));
+
+    if !before || !after { return None; }
+  }
+
+  let clean = digits.replace('_', "");
+
+  if radix != 10 && suffix == "n" {
+    return BigInt::parse_bytes(clean.as_bytes(), radix).map(|x| format!("{x}n"));
+  }
+  if radix != 10 {
+    return i64::from_str_radix(&clean, radix).ok().map(|x| x.to_string());
+  }
+
+  // `parse` also takes words like `inf`, so only digits, one dot and an
+  // exponent are let through.
+  let (mantissa, exponent) = clean.split_once(['e', 'E']).unwrap_or((&clean, "0"));
+  let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+
+  let valid = mantissa.chars().filter(|x| *x == '.').count() <= 1 && !mantissa.ends_with('.')
+    && mantissa.chars().all(|x| x.is_ascii_digit() || x == '.')
+    && !exponent.is_empty() && exponent.chars().all(|x| x.is_ascii_digit());
+
+  if !valid { return None; }
+
+  let integral = !clean.contains(['.', 'e', 'E']);
+
+  return match suffix {
+    "n" => if integral { Some(clean + suffix) } else { None },
+    "d" => Decimal::parse(&clean).map(|_| clean + suffix),
+    _ if integral && clean.trim_start_matches('0') == LEAST => Some(LEAST.to_string()),
+    _ if integral => clean.parse::<i64>().ok().map(|_| clean),
+    _ => Some(clean),
+  };
+}
+// the type of a normalized literal.
+pub fn numeric(text: &str) -> Type {
+  if text.ends_with('n') { return Type::BigInt }
+  if text.ends_with('d') { return Type::Decimal }
+
+  return if text.contains(['.', 'e', 'E']) { Type::Number } else { Type::Integer };
 }
 
 impl Lexer {
   pub fn new(logger: Logger) -> Self {
     let lexer = Lexer {
       tokens: vec![],
+      errors: vec![],
+      templates: vec![],
+      docs: vec![],
       source: logger.get_chars(),
       logger: logger.wrap(),
       coords: [1, 1],
       pointer: 0,
+      offset: 0,
     };
 
     return lexer;
   }
-  pub fn tokenize(mut self) -> (Vec<Token>, Box<Logger>) {
+  pub fn tokenize(mut self) -> (Vec<Token>, Box<Logger>, Vec<String>) {
     while self.pointer < self.source.len() 
       { self.get_next(); }
     
-    return (self.tokens, self.logger);
+    return (self.tokens, self.logger, self.errors);
   }
 
   fn advance(&mut self) {
+    self.offset += self.current().len_utf8();
     self.pointer += 1;
     self.coords[1] += 1;
   }
   fn current(&self) -> char {
     return self.source[self.pointer];
   }
-  fn append<S:ToString>(&mut self, text: S, class: Class, coords: [usize; 2]) {
-    self.tokens.push(Token::init(class, text, coords));
+  // adds a token spanning from the byte offset `start` up to the current one.
+  fn append<S:ToString>(&mut self, text: S, class: Class, coords: [usize; 2], start: usize) {
+    let mut token = Token::init(class, text, coords, Span::new(start, self.offset));
+
+    // doc comments are dropped unless a declaration follows right after them.
+    let docs = std::mem::take(&mut self.docs);
+    if class == Class::Keyword && matches!(token.text.as_str(), "set" | "var" | "type") && !docs.is_empty() {
+      token.doc = Some(docs.join("\n"));
+    }
+
+    self.tokens.push(token);
   }
   fn push_c(&mut self, class: Class) {
-    self.append(self.current(), class, self.coords);
+    let (char, coords, start) = (self.current(), self.coords, self.offset);
+
     self.advance();
+    self.append(char, class, coords, start);
   }
 }
 
 impl Lexer {
+  // lexes a string from its opening quote, or from the `}` closing an
+  // interpolated expression inside of it, up to its next `{` or its end.
+  fn string(&mut self, resumed: bool) {
+    let coords = self.coords;
+    let start = self.offset;
+    let mut text = String::new(); self.advance();
+
+    let class = loop {
+      match self.current() {
+        '"' => {
+          self.advance();
+          break if resumed { Class::StringTail } else { Class::String };
+        },
+        '{' if self.source[self.pointer + 1] == '}' => {
+          text += "{}"; self.advance(); self.advance();
+        },
+        '{' => {
+          self.advance();
+          self.templates.push(0);
+          break if resumed { Class::StringPart } else { Class::StringHead };
+        },
+        '\0' if self.pointer == self.source.len() - 1 => {
+          self.errors.push(self.logger.error("unterminated string", "expected a closing '\"'.", Span::new(start, start + 1)));
+          break if resumed { Class::StringTail } else { Class::String };
+        },
+        '\\' => {
+          let at = self.offset;
+          self.advance();
+          text.push(self.escape(at));
+        },
+        // a windows line ending inside of a string is kept as only '\n'.
+        '\r' if self.source[self.pointer + 1] == '\n' => self.advance(),
+        '\n' => {
+          text.push('\n'); self.advance();
+          self.coords[0] += 1;
+          self.coords[1] = 1;
+        },
+
+        _ => { text.push(self.current()); self.advance(); },
+      }
+    };
+
+    self.append(text, class, coords, start);
+  }
+  // decodes the escape after the backslash at `at`, keeping it as written if
+  // it is invalid.
+  fn escape(&mut self, at: usize) -> char {
+    let char = self.current();
+
+    let decoded = match char {
+      'n' => '\n',
+      't' => '\t',
+      'r' => '\r',
+      '0' => '\0',
+      '\\' | '"' | '{' | '}' => char,
+      'u' => return self.unicode(at),
+      _ => {
+        let spot = Span::new(at, self.offset + char.len_utf8());
+        self.errors.push(self.logger.error("invalid escape", format!("\"\\{char}\" is not a valid escape."), spot));
+        return '\\';
+      },
+    };
+
+    self.advance();
+    return decoded;
+  }
+  fn unicode(&mut self, at: usize) -> char {
+    self.advance();
+
+    let mut digits = String::new();
+
+    if self.current() == '{' {
+      self.advance();
+
+      while self.current().is_ascii_hexdigit() {
+        digits.push(self.current()); self.advance();
+      }
+
+      if self.current() == '}' {
+        self.advance();
+
+        let decoded = u32::from_str_radix(&digits, 16).ok().filter(|_| digits.len() <= 6).and_then(char::from_u32);
+        if let Some(char) = decoded { return char; }
+      }
+    }
+
+    self.errors.push(self.logger.error("invalid escape", format!("\"\\u{{{digits}}}\" is not a valid unicode escape."), Span::new(at, self.offset)));
+    return char::REPLACEMENT_CHARACTER;
+  }
+
+  // skips a `#` line comment or a nestable `#[ ... ]#` block, keeping the text
+  // of a `##` doc comment for the declaration after it.
+  fn comment(&mut self) {
+    if self.source.get(self.pointer + 1) == Some(&'[') {
+      return self.block();
+    }
+
+    self.advance();
+    let doc = self.current() == '#';
+    if doc { self.advance(); }
+
+    let mut text = String::new();
+    while !matches!(self.current(), '\n' | '\0') {
+      text.push(self.current()); self.advance();
+    }
+
+    if doc {
+      let text = text.strip_suffix('\r').unwrap_or(&text);
+      self.docs.push(text.strip_prefix(' ').unwrap_or(text).to_string());
+    }
+  }
+  fn block(&mut self) {
+    let start = self.offset;
+    let mut depth = 0;
+
+    loop {
+      match (self.current(), self.source.get(self.pointer + 1)) {
+        ('\0', _) if self.pointer == self.source.len() - 1 => {
+          let spot = Span::new(start, start + 2);
+          self.errors.push(self.logger.error("unterminated comment", "expected a closing ']#'.", spot));
+          return;
+        },
+        ('#', Some('[')) => { depth += 1; self.advance(); self.advance(); },
+        (']', Some('#')) => {
+          depth -= 1; self.advance(); self.advance();
+          if depth == 0 { return; }
+        },
+        ('\n', _) => {
+          self.advance();
+          self.coords[0] += 1;
+          self.coords[1] = 1;
+        },
+        _ => self.advance(),
+      }
+    }
+  }
+
+  fn number(&mut self) {
+    let coords = self.coords;
+    let start = self.offset;
+    let mut raw = String::new();
+
+    loop {
+      let char = self.current();
+      let signed = matches!(char, '+' | '-') && radix(&raw) == 10 && raw.ends_with(['e', 'E']);
+
+      if !(char.is_ascii_alphanumeric() || char == '_' || char == '.' || signed) { break; }
+      raw.push(char); self.advance();
+    }
+
+    let text = if let Some(text) = numeral(&raw) { text } else {
+      let spot = Span::new(start, self.offset);
+      self.errors.push(self.logger.error("invalid number", format!("{raw:?} is not a valid number."), spot));
+
+      "0".to_string()
+    };
+
+    self.append(text, Class::Number, coords, start);
+  }
+
   fn get_next(&mut self) {
     let char = self.current();
     let coords = self.coords;
+    let start = self.offset;
 
     match char {
-      _ if char.is_ascii_alphabetic() ||
-            char == '_' => {
+      _ if is_xid_start(char) || char == '_' => {
         let mut text = String::new();
-        while self.current().is_ascii_alphanumeric() || self.current() == '_' {
+        while is_xid_continue(self.current()) {
           text.push(self.current()); self.advance();
         }
 
         let class = match text.as_str() {
           "true" | "false" => Class::Bool,
 
-          "use" | "set" | "var" | "emit" | "if" | "else" | "type"
+          "use" | "set" | "var" | "emit" | "if" | "else" | "type" |
+          "while" | "for" | "in" | "break" | "continue"
             => Class::Keyword,
           
           _ => Class::Identifier,
         };
 
-        self.append(text, class, coords);
-      },
-      _ if char.is_ascii_digit() => {
-        let mut text = String::new();
-        let mut dots = 0;
-
-        loop {
-          match self.current() {
-            '.' => { dots += 1; text += "."; self.advance(); },
-            '0'..='9' => { text.push(self.current()); self.advance(); },
-
-            _ => break,
-          }
-
-          if dots == 2 { break; }
-        }
-
-        self.append(text, Class::Number, coords);
-      },
-      '"' => {
-        let mut text = String::new(); self.advance();
-
-        loop {
-          match self.current() {
-            '"' => { self.advance(); break; },
-            '\\' => {
-              self.advance(); text += "\\";
-              text.push(self.current()); self.advance();
-            },
-
-            _ => { text.push(self.current()); self.advance(); },
-          }
-        }
-
-        self.append(text, Class::String, coords)
+        self.append(text, class, coords, start);
       },
+      _ if char.is_ascii_digit() => self.number(),
+      '"' => self.string(false),
 
       '+' | '*' | '/' | '%' => {
         self.advance();
         if self.current() == '=' {
-          self.append(char.to_string() + "=", Class::MathOp, coords);
           self.advance();
+          self.append(char.to_string() + "=", Class::MathOp, coords, start);
         } else {
-          self.append(char, Class::MathOp, coords);
+          self.append(char, Class::MathOp, coords, start);
         };
       },
 
       '=' => {
         self.advance();
         match self.current() {
-          '=' => {self.advance(); self.append("==", Class::BoolOp, coords) },
-          _ => self.append("=", Class::Assign, coords),
+          '=' => {self.advance(); self.append("==", Class::BoolOp, coords, start) },
+          _ => self.append("=", Class::Assign, coords, start),
         }
       },
 
       '-' => {
         self.advance();
         match self.current() {
-          '=' => { self.advance(); self.append("-=", Class::MathOp, coords) },
-          '>' => { self.advance(); self.append("->", Class::Arrow, coords) },
-          _ => self.append("-", Class::MathOp, coords)
+          '=' => { self.advance(); self.append("-=", Class::MathOp, coords, start) },
+          '>' => { self.advance(); self.append("->", Class::Arrow, coords, start) },
+          _ => self.append("-", Class::MathOp, coords, start)
         }
       },
 
       '!' | '<' | '>' => {
         self.advance();
         if self.current() == '=' {
-          self.append(char.to_string() + "=", Class::BoolOp, coords);
           self.advance();
+          self.append(char.to_string() + "=", Class::BoolOp, coords, start);
         } else {
-          self.append(char, Class::BoolOp, coords);
+          self.append(char, Class::BoolOp, coords, start);
         };
       },
 
       '|' | '&' | '^' => self.push_c(Class::LogicOp),
 
       '[' => self.push_c(Class::LeftBrace),
-      '{' => self.push_c(Class::LeftBrack),
+      '{' => {
+        if let Some(depth) = self.templates.last_mut() { *depth += 1 }
+        self.push_c(Class::LeftBrack)
+      },
       '(' => self.push_c(Class::LeftParen),
 
       ']' => self.push_c(Class::RightBrace),
-      '}' => self.push_c(Class::RightBrack),
+      '}' => match self.templates.last_mut() {
+        Some(0) => { self.templates.pop(); self.string(true) },
+        Some(depth) => { *depth -= 1; self.push_c(Class::RightBrack) },
+        None => self.push_c(Class::RightBrack),
+      },
       ')' => self.push_c(Class::RightParen),
 
       '.' => self.push_c(Class::Dot),
@@ -154,19 +392,142 @@ impl Lexer {
       ':' => self.push_c(Class::Colon),
       ';' => self.push_c(Class::SemiColon),
       
-      ' ' => self.advance(),
+      // a '\r' left by a windows line ending is only whitespace.
+      ' ' | '\t' | '\r' => self.advance(),
       '\n' => {
         self.advance();
         self.coords[0] += 1;
         self.coords[1] = 1;
       },
-      '\0' => self.push_c(Class::Eof),
-      '#' => while self.current() != '\n' { self.advance() }
+      // the eof token is empty, as the '\0' after the source is not in it.
+      '\0' => { self.append(char, Class::Eof, coords, start); self.advance(); },
+      '#' => self.comment(),
       
       _ => {
-        println!("{}", self.logger.error("invalid character", format!("{char:?} is not recognized."), coords));
+        let spot = Span::new(start, start + char.len_utf8());
+        self.errors.push(self.logger.error("invalid character", format!("{char:?} is not recognized."), spot));
         self.advance();
       },
     };
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lex(source: &str) -> (Vec<Token>, Vec<String>) {
+    let (tokens, _, errors) = Lexer::new(Logger::new("test.baf".into(), source.into())).tokenize();
+    return (tokens, errors);
+  }
+
+  #[test]
+  fn decimal_exponents_past_the_limit_are_errors() {
+    for source in ["1e99999999999d", "1e-99999999999d", "1e99999999999999999999d", "1e4294967295d"] {
+      let (tokens, errors) = lex(source);
+
+      assert_eq!(errors.len(), 1, "{source}");
+      assert_eq!(tokens[0].text, "0");
+    }
+
+    let (tokens, errors) = lex("1.5e3d");
+    assert!(errors.is_empty());
+    assert_eq!(tokens[0].text, "1.5e3d");
+  }
+
+  #[test]
+  fn escapes_are_decoded() {
+    let (tokens, errors) = lex(r#""a\n\t\r\\\"\0\{\}\u{48}\u{1F600}b""#);
+
+    assert!(errors.is_empty());
+    assert_eq!(tokens[0].text, "a\n\t\r\\\"\0{}H\u{1F600}b");
+  }
+
+  #[test]
+  fn bad_escapes_are_errors() {
+    for source in [r#""\q""#, r#""\u{}""#, r#""\u{110000}""#, r#""\u{1234567}""#, r#""\u48""#] {
+      let (_, errors) = lex(source);
+
+      assert_eq!(errors.len(), 1, "{source}");
+      assert!(errors[0].contains("invalid escape"), "{source}");
+    }
+  }
+
+  #[test]
+  fn unterminated_strings_are_errors() {
+    let (tokens, errors) = lex("\"abc");
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("unterminated string"));
+    assert_eq!(tokens[0].text, "abc");
+    assert_eq!(tokens.last().unwrap().class, Class::Eof);
+  }
+
+  #[test]
+  fn interpolated_strings_are_split_around_their_expressions() {
+    let (tokens, errors) = lex(r#""a {x} b {{y}} c {}""#);
+    assert!(errors.is_empty());
+
+    let found = tokens.iter().map(|x| (x.class, x.text.as_str())).collect::<Vec<_>>();
+    assert_eq!(found, [
+      (Class::StringHead, "a "),
+      (Class::Identifier, "x"),
+      (Class::StringPart, " b "),
+      (Class::LeftBrack, "{"),
+      (Class::Identifier, "y"),
+      (Class::RightBrack, "}"),
+      (Class::StringTail, " c {}"),
+      (Class::Eof, "\0"),
+    ]);
+  }
+
+  #[test]
+  fn number_literals_are_normalized() {
+    let cases = [
+      ("0x1F", "31"), ("0o17", "15"), ("0b101", "5"), ("1_000_000", "1000000"),
+      ("1.5e3", "1.5e3"), ("2E-3", "2E-3"), ("0xffn", "255n"), ("1_0.5d", "10.5d"),
+    ];
+
+    for (source, text) in cases {
+      let (tokens, errors) = lex(source);
+
+      assert!(errors.is_empty(), "{source}: {errors:?}");
+      assert_eq!((tokens[0].class, tokens[0].text.as_str()), (Class::Number, text));
+    }
+  }
+
+  #[test]
+  fn malformed_number_literals_are_errors() {
+    for source in ["1__0", "1_", "1.2.3", "1.", "0x", "0b12", "1e", "1.5n", "9223372036854775809", "0xfd.5"] {
+      let (tokens, errors) = lex(source);
+
+      assert_eq!(errors.len(), 1, "{source}: {errors:?}");
+      assert!(errors[0].contains("invalid number"), "{source}");
+      assert_eq!(tokens[0].text, "0", "{source}");
+    }
+  }
+
+  #[test]
+  fn comments_are_skipped() {
+    let (tokens, errors) = lex("a #[ b #[ c ]# d ]# e # f");
+    assert!(errors.is_empty());
+
+    let found = tokens.iter().map(|x| x.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(found, ["a", "e", "\0"]);
+
+    let (_, errors) = lex("a #[ b #[ c ]#");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("unterminated comment"));
+  }
+
+  #[test]
+  fn tabs_windows_line_endings_and_unicode_are_accepted() {
+    let (tokens, errors) = lex("set\tnaïve = 1;\r\nset 名前 = \"a\r\nb\";\r\n");
+    assert!(errors.is_empty(), "{errors:?}");
+
+    assert_eq!((tokens[1].class, tokens[1].text.as_str()), (Class::Identifier, "naïve"));
+    assert_eq!((tokens[6].text.as_str(), tokens[6].coords), ("名前", [2, 5]));
+    assert_eq!((tokens[8].text.as_str(), tokens[8].coords), ("a\nb", [2, 10]));
+    assert_eq!(tokens[9].coords, [3, 3]);
+  }
+}
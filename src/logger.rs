@@ -1,44 +1,140 @@
 #![allow(unused)]
-use crate::utils::Coords;
+use unicode_width::UnicodeWidthChar;
+
+use crate::token::Span;
+use crate::utils::Spanned;
+
+const TAB: usize = 4;
 
 #[derive(Debug, Clone)]
 pub struct Logger {
   filename: String,
+  text: String,
   source: Vec<String>,
 }
 
 impl Logger {
-  pub fn new(filename: String, source: String) -> Self {
-    let source = source.lines().into_iter().map(|x| x.into()).collect::<Vec<String>>();
-    Self { filename, source, }
+  pub fn new(filename: String, text: String) -> Self {
+    let source = text.split('\n').map(|x| x.strip_suffix('\r').unwrap_or(x).into()).collect::<Vec<String>>();
+    Self { filename, text, source, }
+  }
+
+  pub fn filename(&self) -> &str {
+    return &self.filename;
   }
 
   pub fn get_chars(&self) -> Vec<char> {
-    return (self.source.join("\n") + "\0").chars().collect();
+    return (self.text.clone() + "\0").chars().collect();
   }
+  // the line and column, both counted from one, that a byte offset falls on.
+  pub fn locate(&self, offset: usize) -> [usize; 2] {
+    let before = &self.text[..offset.min(self.text.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().unwrap().chars().count() + 1;
+
+    return [line, col];
+  }
+
+  fn message(&self, kind: &str, header: String, message: String, span: Span) -> String {
+    let [line, col] = self.locate(span.start);
+    let [stop, end] = self.locate(span.end);
+    let (shown, starts) = expand(&self.source[line - 1]);
+
+    // spans running onto later lines are underlined to the end of their first.
+    let len = if stop == line { end.saturating_sub(col) } else { starts.len() - col };
 
-  fn message(&self, kind: &str, header: String, message: String, info: [usize; 3]) -> String {
-    let (line, col, len) = (info[0], info[1], info[2]);
+    // a span can reach past what is shown of the line, as over a '\r', one
+    // column per missing char.
+    let column = |index: usize| {
+      let last = starts.len() - 1;
+      if index <= last { starts[index] } else { starts[last] + index - last }
+    };
+
+    let start = column(col - 1);
+    let width = column(col - 1 + len) - start;
 
     let buffr = ' '.to_string().repeat(line.to_string().len());
-    let space = ' '.to_string().repeat(col -1);
-    let value = '~'.to_string().repeat(len - 1);
+    let space = ' '.to_string().repeat(start);
+    let value = '~'.to_string().repeat(width.max(1) - 1);
 
-    let info = vec![
+    let info = [
       format!("{kind} -> {}[{}:{}]: {header}", self.filename, line, col),
-      format!("{} | {}", line, self.source[line - 1].clone()),
-      format!("{} | {}^{} {}", buffr, space, value, message.to_string())
+      format!("{} | {}", line, shown),
+      format!("{} | {}^{} {}", buffr, space, value, message)
     ].join("\n");
 
     return info;
   }
-  pub fn error<S:ToString, V:ToString, C:Coords>(&self, header: S, message: V, spot: C) -> String {
-    return self.message("error", header.to_string(), message.to_string(), spot.coords());
+  pub fn error<S:ToString, V:ToString, C:Spanned>(&self, header: S, message: V, spot: C) -> String {
+    return self.message("error", header.to_string(), message.to_string(), spot.span());
+  }
+  pub fn inform<S:ToString, V:ToString, C:Spanned>(&self, header: S, message: V, spot: C) -> String {
+    return self.message("info", header.to_string(), message.to_string(), spot.span());
   }
-  pub fn inform<S:ToString, V:ToString, C:Coords>(&self, header: S, message: V, spot: C) -> String {
-    return self.message("info", header.to_string(), message.to_string(), spot.coords());
+  pub fn warn<S:ToString, V:ToString, C:Spanned>(&self, header: S, message: V, spot: C) -> String {
+    return self.message("warning", header.to_string(), message.to_string(), spot.span());
   }
-  pub fn warn<S:ToString, V:ToString, C:Coords>(&self, header: S, message: V, spot: C) -> String {
-    return self.message("warning", header.to_string(), message.to_string(), spot.coords());
+}
+
+// expands tabs out to spaces, giving the line as it is shown along with the
+// display column each char starts at, and one past its last.
+fn expand(line: &str) -> (String, Vec<usize>) {
+  let mut shown = String::new();
+  let mut starts = vec![];
+  let mut column = 0;
+
+  for char in line.chars() {
+    starts.push(column);
+
+    let width = if char == '\t' { TAB - column % TAB } else { char.width().unwrap_or(0) };
+
+    match char {
+      '\t' => shown += &" ".repeat(width),
+      _ if char.is_control() => (),
+      _ => shown.push(char),
+    }
+
+    column += width;
   }
-}
\ No newline at end of file
+
+  starts.push(column);
+  return (shown, starts);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // the line of an error pointing at `spot` in `text`, and the underline below it.
+  fn pointed(text: &str, spot: Span) -> (String, String) {
+    let error = Logger::new("test.baf".into(), text.into()).error("bad", "here", spot);
+    let lines = error.lines().collect::<Vec<&str>>();
+
+    return (lines[1].to_string(), lines[2].to_string());
+  }
+
+  #[test]
+  fn tabs_are_expanded_to_the_next_stop() {
+    let (line, under) = pointed("\tab\tc", Span::new(4, 5));
+
+    assert_eq!(line, "1 |     ab  c");
+    assert_eq!(under, "  |         ^ here");
+  }
+
+  #[test]
+  fn wide_chars_are_underlined_by_their_width() {
+    let (line, under) = pointed("set 名前 = x;\r\n", Span::new(4, 10));
+
+    assert_eq!(line, "1 | set 名前 = x;");
+    assert_eq!(under, "  |     ^~~~ here");
+  }
+
+  #[test]
+  fn offsets_are_located_by_char() {
+    let logger = Logger::new("test.baf".into(), "é\r\nab".into());
+
+    assert_eq!(logger.locate(2), [1, 2]);
+    assert_eq!(logger.locate(5), [2, 2]);
+  }
+}
@@ -1,10 +1,10 @@
 #![allow(non_snake_case)]
-
-use std::{error::Error, fs::read_to_string, io::{self, Write as _}};
+#![allow(clippy::needless_return)]
 
 use lexer::Lexer;
 use logger::Logger;
 use parser::Parser;
+use repl::Repl;
 use runtime::Runtime;
 use utils::Color;
 mod token;
@@ -14,25 +14,24 @@ mod utils;
 mod syntax;
 mod parser;
 mod runtime;
+mod checker;
 mod envirnment;
+mod compiler;
+mod decimal;
+mod repl;
 
-fn input(prompt: &str) -> String {
-	print!("{}", prompt); 
-	io::stdout().flush().unwrap();
-	let mut buffer = String::new();
-	
-	if let Err(err) = io::stdin().read_line(&mut buffer) {
-		println!("{err}")
-	};
+const USAGE: &str = "usage: baffler [repl]
+       baffler run <file> [-- args...]
+       baffler check <file>
+       baffler tokens <file>
+       baffler ast <file>";
 
-	return buffer.trim().to_string();
+fn usage(message: &str) -> ! {
+	println!("{}: {message}\n{USAGE}", "error".color(31));
+	std::process::exit(1);
 }
 
-fn get_content() -> [String; 2] {
-	let filename = if let Some(arg) = std::env::args().nth(1) { arg } else {
-		input("filename: ")
-	};
-
+fn get_content(filename: String) -> Logger {
 	let source = match std::fs::read_to_string(&filename) {
 		Ok(src) => src,
 		Err(err) => {
@@ -41,16 +40,106 @@ fn get_content() -> [String; 2] {
 		},
 	};
 
-	[filename, source]
+	Logger::new(filename, source)
+}
+
+// reads the command, the file it takes and the arguments for the script, or
+// what is wrong with them. no command at all opens the repl.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<(String, String, Vec<String>)>, String> {
+	let command = match args.next() {
+		Some(arg) if arg != "repl" => arg,
+		_ => return Ok(None),
+	};
+
+	// a lone filename is run, as it always has been.
+	let (command, filename) = match command.as_str() {
+		"run" | "check" | "tokens" | "ast" => match args.next() {
+			Some(filename) => (command, filename),
+			None => return Err(format!("{command:?} expects a file.")),
+		},
+		_ if command.starts_with('-') => return Err(format!("unknown option {command:?}.")),
+		_ => ("run".to_string(), command),
+	};
+
+	let extra = args.collect::<Vec<String>>();
+
+	let script = match extra.split_first() {
+		None => vec![],
+		Some((first, rest)) if first == "--" && command == "run" => rest.to_vec(),
+		Some((first, _)) => return Err(format!("unexpected argument {first:?}.")),
+	};
+
+	return Ok(Some((command, filename, script)));
 }
 
 fn main() {
-	let [filename, source] = get_content();
-	let logger = Logger::new(filename, source);
+	let (command, filename, script) = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(parsed)) => parsed,
+		Ok(None) => return Repl::init().start(),
+		Err(message) => usage(&message),
+	};
+
+	let logger = get_content(filename);
+
+	match command.as_str() {
+		"tokens" => {
+			let (tokens, _, errors) = Lexer::new(logger).tokenize();
+			tokens.iter().for_each(|x| println!("{x}"));
+
+			if !errors.is_empty() {
+				errors.iter().for_each(|x| println!("{x}"));
+				std::process::exit(1);
+			}
+		},
+		"ast" => {
+			let (nodes, _, errors) = Parser::init(Lexer::new(logger)).parse();
+			nodes.iter().for_each(|x| println!("{x:?}"));
+
+			if !errors.is_empty() {
+				errors.iter().for_each(|x| println!("{x}"));
+				std::process::exit(1);
+			}
+		},
+		"check" => {
+			let (_, logger) = runtime::verify(Parser::init(Lexer::new(logger)));
+			println!("{}: no errors found.", logger.filename());
+		},
+		_ => {
+			runtime::ARGS.set(script).unwrap();
+			let runtime = Runtime::init(Parser::init(Lexer::new(logger)));
+
+			if let Err(err) = runtime.interperate() {
+				println!("{err}");
+				std::process::exit(1);
+			}
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(args: &[&str]) -> Result<Option<(String, String, Vec<String>)>, String> {
+		return parse_args(args.iter().map(|x| x.to_string()));
+	}
+
+	#[test]
+	fn commands_take_a_file() {
+		assert_eq!(parse(&[]), Ok(None));
+		assert_eq!(parse(&["repl"]), Ok(None));
+		assert_eq!(parse(&["check", "a.baf"]), Ok(Some(("check".into(), "a.baf".into(), vec![]))));
+		assert_eq!(parse(&["a.baf"]), Ok(Some(("run".into(), "a.baf".into(), vec![]))));
+		assert_eq!(parse(&["tokens"]), Err("\"tokens\" expects a file.".into()));
+		assert_eq!(parse(&["--help"]), Err("unknown option \"--help\".".into()));
+	}
 
-	let lexer = Lexer::new(logger);
-	let parser = Parser::init(lexer);
-	let runtime = Runtime::init(parser);
+	#[test]
+	fn arguments_after_a_dash_dash_go_to_the_script() {
+		let script = vec!["-x".to_string(), "--".to_string()];
 
-	runtime.interperate();
+		assert_eq!(parse(&["run", "a.baf", "--", "-x", "--"]), Ok(Some(("run".into(), "a.baf".into(), script))));
+		assert_eq!(parse(&["run", "a.baf", "x"]), Err("unexpected argument \"x\".".into()));
+		assert_eq!(parse(&["ast", "a.baf", "--", "x"]), Err("unexpected argument \"--\".".into()));
+	}
 }
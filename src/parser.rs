@@ -1,41 +1,71 @@
-use crate::{lexer::Lexer, logger::Logger, syntax::{Expr, Node}, token::{Class, Token}, utils::Wrapper};
+use crate::{lexer::{Lexer, LEAST}, logger::Logger, syntax::{Expr, Node}, token::{Class, Span, Token}, utils::{Spanned, Wrapper}};
 
 pub struct Parser {
   logger: Box<Logger>,
   tokens: Vec<Token>,
   pointer: usize,
+  // the byte offset just past the last token taken.
+  end: usize,
+  restrict: bool,
+  errors: Vec<String>,
+  panic: bool,
 }
 
 impl Parser {
   pub fn init(lexer: Lexer) -> Parser {
-    let ( tokens, logger ) = lexer.tokenize();
+    let ( tokens, logger, errors ) = lexer.tokenize();
 
     return Parser {
-      logger, tokens, pointer: 0,
+      logger, tokens, pointer: 0, end: 0, restrict: false,
+      errors, panic: false,
     };
   }
 
-  pub fn parse(mut self) -> (Vec<Node>, Box<Logger>) {
+  pub fn parse(mut self) -> (Vec<Node>, Box<Logger>, Vec<String>) {
     let mut nodes = vec![];
 
     while self.current().class != Class::Eof {
       nodes.push(self.parse_node());
     }
 
-    return (nodes, self.logger);
+    return (nodes, self.logger, self.errors);
   }
 }
 
 impl Parser {
-  fn error<V:ToString, S:ToString>(&self, header: V , message: S, token: &Token) {
-    println!("{}", self.logger.error(header, message.to_string(), token));
-    std::process::exit(1)
+  // only the first error of a statement is recorded, the rest are likely
+  // caused by it. parsing resumes at the next statement boundary.
+  fn error<V:ToString, S:ToString, C:Spanned>(&mut self, header: V , message: S, spot: C) {
+    if self.panic { return; }
+
+    self.errors.push(self.logger.error(header, message.to_string(), spot));
+    self.panic = true;
+  }
+  // skips to the end of the failed statement, or to the start of the next
+  // one if its ';' was left off.
+  fn synchronize(&mut self) {
+    loop {
+      let token = self.current();
+
+      match token.class {
+        Class::SemiColon => { self.advance(); break; },
+        Class::RightBrack | Class::Eof => break,
+        Class::Keyword if matches!(token.text.as_str(), "set" | "var" | "use" | "type" | "emit" | "while" | "for") => break,
+        _ => self.advance(),
+      }
+    }
+
+    self.panic = false;
   }
 }
 
 impl Parser {
   fn advance(&mut self) {
-    self.pointer += 1;
+    self.end = self.tokens[self.pointer].span.end;
+
+    if self.pointer < self.tokens.len() - 1 {
+      self.pointer += 1;
+    }
   }
   fn current(&self) -> Token {
     return self.tokens[self.pointer].clone();
@@ -43,16 +73,15 @@ impl Parser {
   fn tokenth(&self, offset: isize) -> Token {
     let index = (self.pointer as isize + offset) as usize;
 
-    return if index < self.tokens.len() 
-      { self.tokens[index].clone() } else 
-      { self.tokens[self.tokens.len() - 1].clone() };
+    return if index < self.tokens.len()
+      { self.tokens[index].clone() } else { self.tokens[self.tokens.len() - 1].clone() };
   }
   fn consume<S:ToString>(&mut self, class: Class, message: S) -> Token {
-    let token = self.current(); self.advance();
+    let token = self.current();
 
     if token.class != class {
       self.error(format!("unexpected {}", token.class), message, &token);
-    }
+    } else { self.advance(); }
 
     return token;
   }
@@ -62,7 +91,32 @@ impl Parser {
 
     return token;
   }
+  // the span from the byte offset `start` through the last token taken.
+  fn span(&self, start: usize) -> Span {
+    return Span::new(start, self.end.max(start));
+  }
+  // stands in for a missing expression, as an empty span after the last token.
+  fn null(&self) -> Expr {
+    let prev = self.tokenth(-1);
+    let span = Span::new(prev.span.end, prev.span.end);
+
+    return Expr::NullVoid { prev, span };
+  }
+  fn nothing(&self) -> Node {
+    let expr = self.null();
+    return Node::Expression { span: expr.span(), expr };
+  }
+  // struct constructors are not allowed directly in the head of an if, while
+  // or for, as `if flag { ... }` would otherwise read as `flag { ... }`.
+  fn restricted<F:Fn(&mut Parser) -> T, T>(&mut self, restrict: bool, grab: F) -> T {
+    let outer = std::mem::replace(&mut self.restrict, restrict);
+    let value = grab(self);
+    self.restrict = outer;
+
+    return value;
+  }
   fn collect<F:Fn(&mut Parser) -> Expr>(&mut self, delims: [Class; 2], grab: F) -> Vec<Expr> {
+    let outer = std::mem::replace(&mut self.restrict, false);
     let mut items: Vec<Expr> = vec![];
     let mut able: bool = true;
     self.consume(delims[0], format!("expected {} to start series.", delims[1]));
@@ -81,22 +135,115 @@ impl Parser {
         _ => if able {
           able = false; items.push(grab(self))
         } else {
-          self.error("invalid series", format!("expected ',' before next expression."), &token);
+          self.error("invalid series", "expected ',' before next expression.", &token);
           break;
         }, 
       }
     }
 
-    if items.len() == 0 {
-      return vec![Expr::NullVoid { prev: self.tokenth(-1) }];
-    }
-
+    self.restrict = outer;
     return items;
   }
 }
 
 impl Parser {
+  // binding powers of the infix operators, as (left, right) pairs. a left
+  // power lower than its right one makes the operator left associative.
+  //
+  //   |                     1, 2
+  //   ^                     3, 4
+  //   &                     5, 6
+  //   == !=                 7, 8
+  //   < > <= >=             9, 10
+  //   + -                   11, 12
+  //   * / %                 13, 14
+  //
+  // the prefix operators - + ! bind with a power of 15, tighter than any
+  // infix operator but looser than indexing and attribute access.
+  const PREFIX_POWER: u8 = 15;
+
+  fn binding_power(token: &Token) -> Option<(u8, u8)> {
+    let power = match (token.class, token.text.as_str()) {
+      (Class::LogicOp, "|") => (1, 2),
+      (Class::LogicOp, "^") => (3, 4),
+      (Class::LogicOp, "&") => (5, 6),
+
+      (Class::BoolOp, "==" | "!=") => (7, 8),
+      (Class::BoolOp, "<" | ">" | "<=" | ">=") => (9, 10),
+
+      (Class::MathOp, "+" | "-") => (11, 12),
+      (Class::MathOp, "*" | "/" | "%") => (13, 14),
+
+      _ => return None,
+    };
+
+    return Some(power);
+  }
+
   pub fn expect_expr(&mut self) -> Expr {
+    return self.climb(0);
+  }
+
+  fn climb(&mut self, min: u8) -> Expr {
+    let mut lhs = self.expect_prefix();
+
+    loop {
+      let oper = self.current();
+      let (left, right) = match Self::binding_power(&oper) {
+        Some(power) => power,
+        None => break,
+      };
+
+      if left < min { break; }
+      self.advance();
+
+      let rhs = self.climb(right);
+      lhs = self.build_binary(lhs, oper, rhs);
+    }
+
+    return lhs;
+  }
+
+  fn expect_prefix(&mut self) -> Expr {
+    let token = self.current();
+
+    return match (token.class, token.text.as_str()) {
+      (Class::MathOp, "-") if self.tokenth(1).class == Class::Number && self.tokenth(1).text == LEAST => {
+        self.advance();
+
+        let mut value = self.grab();
+        value.text = format!("-{LEAST}");
+        value.span = Span::new(token.span.start, value.span.end);
+        value.coords = token.coords;
+
+        Expr::Number { span: value.span, value }
+      },
+      (Class::MathOp, "-" | "+") | (Class::BoolOp, "!") => {
+        self.advance(); let value = self.climb(Self::PREFIX_POWER).wrap();
+        Expr::Unary { span: self.span(token.span.start), oper: token, value }
+      },
+      _ => self.expect_postfix(),
+    };
+  }
+
+  fn expect_postfix(&mut self) -> Expr {
+    let mut expr = self.expect_primary();
+
+    loop {
+      expr = match self.current().class {
+        Class::LeftBrace => self.fetch_index(expr),
+        Class::Colon => self.fetch_attribute(expr),
+        Class::LeftParen => self.fetch_call(expr),
+        Class::LeftBrack if !self.restrict && expr.path().is_some()
+          => self.fetch_construct(expr),
+        _ => break,
+      };
+    }
+
+    return expr;
+  }
+
+  fn expect_primary(&mut self) -> Expr {
     let token = self.current();
 
     let expr = match token.class {
@@ -104,7 +251,7 @@ impl Parser {
         "if" => self.build_conditional(),
         _ => {
           self.error("invalid expression header", format!("expected expression header, but found keyword {}", token.text), &token);
-          Expr::NullVoid { prev: self.tokenth(-1) }
+          self.null()
         }
       },
 
@@ -117,117 +264,182 @@ impl Parser {
         | Class::Identifier 
         => self.fetch_literal(),
 
+      Class::StringHead => self.fetch_interpolated(),
       Class::LeftBrace => self.fetch_array(),
       Class::LeftParen => self.fetch_wrapper(),
       Class::LeftBrack => self.lambda_or_object(),
 
       _ => {
         self.error("invalid expression", format!("expected expression header, but found {}.", token.class), &token);
-        Expr::NullVoid { prev: self.tokenth(-1) }
+        self.null()
       },
     };
 
-    return match self.current().class {
-      Class::LeftBrace => self.fetch_index(expr),
-      Class::MathOp => self.build_operation(expr),
-      Class::BoolOp => self.build_comparison(expr),
-      Class::LogicOp => self.build_logic_chain(expr),
-      Class::Colon => self.fetch_attribute(expr),
-
-      _ => expr
-    };
+    return expr;
   }
 
   fn fetch_literal(&mut self) -> Expr {
     let value = self.grab();
+    let span = value.span;
 
     let expr = match value.class {
-      Class::Identifier => Expr::VarRef { value },
-      Class::String => Expr::String { value },
-      Class::Number => Expr::Number { value },
-      Class::Bool => Expr::Boolean { value },
+      Class::Identifier => Expr::VarRef { value, span },
+      Class::String
+        | Class::StringHead
+        | Class::StringPart
+        | Class::StringTail => Expr::String { value, span },
+      Class::Number if value.text == LEAST => {
+        self.error("invalid number", format!("{LEAST} is past the largest int, and can only be negated."), &value);
+        Expr::Number { value, span }
+      },
+      Class::Number => Expr::Number { value, span },
+      Class::Bool => Expr::Boolean { value, span },
       _ => unreachable!()
     };
 
     return expr;
   }
+  fn fetch_interpolated(&mut self) -> Expr {
+    let outer = std::mem::replace(&mut self.restrict, false);
+    let start = self.current().span.start;
+    let mut parts = vec![self.fetch_literal()];
+
+    loop {
+      parts.push(self.expect_expr());
+      let token = self.current();
+
+      match token.class {
+        Class::StringPart => parts.push(self.fetch_literal()),
+        Class::StringTail => { parts.push(self.fetch_literal()); break; },
+        _ => {
+          self.error("unterminated interpolation", format!("expected '}}' to end the interpolated expression, but found {}.", token.class), &token);
+          break;
+        },
+      }
+    }
+
+    self.restrict = outer;
+    return Expr::Interpolated { parts, span: self.span(start) };
+  }
   fn fetch_array(&mut self) -> Expr {
+    let start = self.current().span.start;
     let value = self.collect(
       [Class::LeftBrace, Class::RightBrace],
       Self::expect_expr
     );
 
-    return Expr::Array { value }
+    return Expr::Array { value, span: self.span(start) }
   }
   fn fetch_fun_call(&mut self) -> Expr {
     let name = self.grab();
     let args = self.collect(
       [Class::LeftParen, Class::RightParen], 
-      Self::expect_expr
+      Self::fetch_argument
     );
 
-    return Expr::FunCall { name, args }
+    return Expr::FunCall { span: self.span(name.span.start), name, args }
+  }
+  fn fetch_construct(&mut self, path: Expr) -> Expr {
+    let start = path.span().start;
+    let mut space = path.path().unwrap();
+    let name = space.pop().unwrap();
+    let attrs = self.collect(
+      [Class::LeftBrack, Class::RightBrack],
+      Self::build_pair
+    );
+
+    for attr in &attrs {
+      if let Expr::TypePair { name, .. } = attr {
+        self.error("invalid field", format!("expected '=' to give {:?} a value.", name.text), name);
+      }
+    }
+
+    return Expr::Construct { space, name, attrs, span: self.span(start) };
+  }
+  fn fetch_call(&mut self, callee: Expr) -> Expr {
+    let args = self.collect(
+      [Class::LeftParen, Class::RightParen], 
+      Self::fetch_argument
+    );
+
+    return Expr::Call { span: self.span(callee.span().start), callee: callee.wrap(), args }
+  }
+  fn fetch_argument(&mut self) -> Expr {
+    if self.current().class == Class::Identifier && self.tokenth(1).class == Class::Assign {
+      let name = self.grab(); self.advance();
+      let attr = self.expect_expr().wrap();
+
+      return Expr::ObjectField { span: self.span(name.span.start), name, attr };
+    }
+
+    return self.expect_expr();
   }
   fn fetch_wrapper(&mut self) -> Expr {
-    self.advance(); let expr = self.expect_expr();
+    self.advance(); let expr = self.restricted(false, Self::expect_expr);
     self.consume(Class::RightParen, "expected ')' after '('");
 
     return expr;
   }
   fn fetch_index(&mut self, parent: Expr) -> Expr {
-    self.advance(); let index = self.expect_expr().wrap();
+    self.advance(); let index = self.restricted(false, Self::expect_expr).wrap();
     self.consume(Class::RightBrace, "expected ']' after '['");
 
-    return Expr::Index { parent: parent.wrap(), index }
+    return Expr::Index { span: self.span(parent.span().start), parent: parent.wrap(), index }
   }
   fn fetch_typeref(&mut self) -> Expr {
-    let base = self.consume(Class::Identifier, "expected typeref name");
+    let start = self.current().span.start;
+
+    if self.current().class == Class::LeftParen {
+      let args = self.collect(
+        [Class::LeftParen, Class::RightParen],
+        Self::fetch_typeref
+      );
+      self.consume(Class::Arrow, "expected '->' after function type arguments");
+      let kind = self.fetch_typeref().wrap();
+
+      return Expr::FunType { args, kind, span: self.span(start) };
+    }
+
+    let mut base = self.consume(Class::Identifier, "expected typeref name");
+    let mut space = vec![];
+
+    while self.current().class == Class::Colon && self.tokenth(1).class == Class::Identifier {
+      self.advance(); space.push(base);
+      base = self.grab();
+    }
+
     let mut arrs = 0;
     while self.current().class == Class::LeftBrace {
       self.advance(); arrs += 1;
       self.consume(Class::RightBrace, "expected ']' after '['");
     }
 
-    return Expr::TypeRef { base, arrs }
+    return Expr::TypeRef { space, base, arrs, span: self.span(start) }
   }
 
-  fn build_operation(&mut self, lhs: Expr) -> Expr {
-    let oper = self.grab(); 
-    let rhs = self.expect_expr();
+  fn build_binary(&mut self, lhs: Expr, oper: Token, rhs: Expr) -> Expr {
+    let span = Span::new(lhs.span().start, rhs.span().end);
+    let (lhs, rhs) = (lhs.wrap(), rhs.wrap());
 
-    return Expr::MathOper { 
-      lhs: lhs.wrap(), 
-      oper, 
-      rhs: rhs.wrap() 
+    return match oper.class {
+      Class::MathOp => Expr::MathOper { lhs, oper, rhs, span },
+      Class::BoolOp => Expr::BoolOper { lhs, oper, rhs, span },
+      Class::LogicOp => Expr::Chained { lhs, stich: oper, rhs, span },
+      _ => unreachable!()
     };
   }
-  fn build_comparison(&mut self, lhs: Expr) -> Expr {
-    let oper = self.grab(); 
-    let rhs = self.expect_expr();
 
-    return Expr::BoolOper { 
-      lhs: lhs.wrap(), 
-      oper, 
-      rhs: rhs.wrap() 
-    };
-  }
-  fn build_logic_chain(&mut self, lhs: Expr) -> Expr {
-    let stich = self.grab(); 
-    let rhs = self.expect_expr();
+  fn fetch_attribute(&mut self, lhs: Expr) -> Expr {
+    self.advance();
 
-    return Expr::Chained { 
-      lhs: lhs.wrap(), 
-      stich, 
-      rhs: rhs.wrap() 
-    };
-  }
+    let value = self.consume(Class::Identifier, "expected attribute name after ':'");
+    let attr = Expr::VarRef { span: value.span, value }.wrap();
 
-  fn fetch_attribute(&mut self, lhs: Expr) -> Expr {
-    self.advance(); let attr = self.expect_expr().wrap();
-    Expr::Attribute { parent: lhs.wrap(), attr }
+    Expr::Attribute { span: self.span(lhs.span().start), parent: lhs.wrap(), attr }
   }
   
   fn lambda_or_object(&mut self) -> Expr {
+    let start = self.current().span.start;
     let list = self.collect(
       [Class::LeftBrack, Class::RightBrack],
       Self::build_pair
@@ -236,43 +448,51 @@ impl Parser {
     match self.current().class {
       Class::Arrow | Class::LeftBrack
         => (),
-      _ => return Expr::Object { attrs: list }
+      _ => return Expr::Object { attrs: list, span: self.span(start) }
     };
 
     let kind = if self.current().class == Class::Arrow {
       self.advance(); self.fetch_typeref()
-    } else { Expr::NullVoid { prev: self.tokenth(-1) } }.wrap();
+    } else { self.null() }.wrap();
 
     let body = self.parse_body().wrap();
 
-    return Expr::Lambda { args: list, kind, body }
+    return Expr::Lambda { args: list, kind, body, span: self.span(start) }
   }
   fn build_conditional(&mut self) -> Expr {
-    self.advance(); 
+    let start = self.grab().span.start;
 
-    let cond = self.expect_expr().wrap();
+    let cond = self.restricted(true, Self::expect_expr).wrap();
     let body = self.parse_body().wrap();
 
     let other = if self.current().text.as_str() == "else" {
       self.advance(); self.parse_body()
-    } else { Node::Expression { expr: Expr::NullVoid { prev: self.tokenth(-1) } } }.wrap();
+    } else { self.nothing() }.wrap();
 
-    Expr::IfExpr { cond, body, other }
+    Expr::If { cond, body, other, span: self.span(start) }
   }
   fn build_pair(&mut self) -> Expr {
     let name = self.consume(Class::Identifier, "expected argument name");
     match self.current().class {
       Class::Assign => {
         self.advance();
-        return Expr::ObjectField { name, attr: self.expect_expr().wrap() };
+        let attr = self.expect_expr().wrap();
+
+        return Expr::ObjectField { span: self.span(name.span.start), name, attr };
       },
       Class::Colon => {
         self.advance();
-        return Expr::TypePair { name, kind: self.fetch_typeref().wrap() }
+        let kind = self.fetch_typeref().wrap();
+
+        let default = if self.current().class == Class::Assign {
+          self.advance(); Some(self.expect_expr().wrap())
+        } else { None };
+
+        return Expr::TypePair { span: self.span(name.span.start), name, kind, default }
       },
       _ => {
-        self.error("unexpected token", format!("expecte '=' or ':' for either obj attr, or type pair."), &self.current());
-        std::process::exit(1);
+        self.error("unexpected token", "expecte '=' or ':' for either obj attr, or type pair.", self.current());
+        return Expr::NullVoid { span: Span::new(name.span.end, name.span.end), prev: name };
       },
     }
   }
@@ -280,6 +500,23 @@ impl Parser {
 
 impl Parser {
   fn parse_node(&mut self) -> Node {
+    let start = self.pointer;
+    let node = self.parse_statement();
+
+    // a statement that failed before its ';' was taken is skipped, so that
+    // the next one is not mistaken for the rest of it.
+    let ended = self.pointer > start && self.tokenth(-1).class == Class::SemiColon;
+
+    if self.panic && ended {
+      self.panic = false;
+    } else if self.panic {
+      self.synchronize();
+      if self.pointer == start { self.advance(); }
+    }
+
+    return node;
+  }
+  fn parse_statement(&mut self) -> Node {
     let token = self.current();
     let node = match token.class {
       Class::Keyword => match token.text.as_str() {
@@ -288,13 +525,24 @@ impl Parser {
         "use" => self.parse_import_pkg(),
         "emit" => self.parse_emit_value(),
         "type" => self.parse_object_dec(),
-        _ => unreachable!()
+        "while" => return self.parse_while_loop(),
+        "for" => return self.parse_for_loop(),
+        "break" => Node::Break { span: token.span, token: self.grab() },
+        "continue" => Node::Continue { span: token.span, token: self.grab() },
+        _ => {
+          let expr = self.expect_expr();
+          Node::Expression { span: expr.span(), expr }
+        },
       },
 
-      Class::Identifier if self.tokenth(1).class == Class::Assign =>
-        self.parse_change_val(),
+      _ => {
+        let expr = self.expect_expr();
+
+        let token = self.current();
+        let assign = token.class == Class::Assign || (token.class == Class::MathOp && token.text.ends_with('='));
 
-      _ => Node::Expression { expr: self.expect_expr() },
+        if assign { self.parse_change_val(expr) } else { Node::Expression { span: expr.span(), expr } }
+      },
     };
 
     self.consume(Class::SemiColon, "expected ';' to terminate statement");
@@ -303,43 +551,66 @@ impl Parser {
   }
   
   fn parse_object_dec(&mut self) -> Node {
-    self.advance(); let name = self.grab();
+    let Token { doc, span, .. } = self.grab();
+    let name = self.grab();
     
     let attrs = self.collect([Class::LeftBrack, Class::RightBrack], |s| {
       let name = s.consume(Class::Identifier, "expected attribute name");
       s.consume(Class::Colon, "expected ':' to divide attr name and type");
       let kind = s.fetch_typeref().wrap();
 
-      Expr::TypePair { name, kind }
+      Expr::TypePair { span: s.span(name.span.start), name, kind, default: None }
     }).into_iter().collect();
 
-    return Node::DeclareType { name, attrs };
+    return Node::DeclareType { name, attrs, doc, span: self.span(span.start) };
   }
   fn parse_set_assign(&mut self) -> Node {
-    self.advance();
+    let Token { doc, span, .. } = self.grab();
     let name = self.consume(Class::Identifier, "expected variable name");
     self.consume(Class::Assign, "expected '=' after `set {name}`");
     let value = self.expect_expr();
 
-    return Node::SetAssign { name, value }
+    return Node::SetAssign { name, value, doc, span: self.span(span.start) }
   }
   fn parse_var_assign(&mut self) -> Node {
-    self.advance();
+    let Token { doc, span, .. } = self.grab();
     let name = self.consume(Class::Identifier, "expected variable name");
     self.consume(Class::Assign, "expected '=' after `set {name}`");
     let value = self.expect_expr();
 
-    return Node::VarAssign { name, value }
+    return Node::VarAssign { name, value, doc, span: self.span(span.start) }
   }
-  fn parse_change_val(&mut self) -> Node {
-    let name = self.consume(Class::Identifier, "expected variable name");
-    self.consume(Class::Assign, "expected '=' after `set {name}`");
+  fn parse_change_val(&mut self, target: Expr) -> Node {
+    let mut root = &target;
+
+    loop {
+      root = match root {
+        Expr::Attribute { parent, .. } |
+        Expr::Index { parent, .. } => parent,
+        _ => break,
+      };
+    }
+
+    if !matches!(root, Expr::VarRef { .. }) {
+      self.error("invalid assignment", "expected a variable, attribute or index before '='", &target);
+    }
+
+    let oper = self.grab();
     let value = self.expect_expr();
 
-    return Node::ChangeVal { name, value }
+    // `x += y` is read as `x = x + y`.
+    let value = if oper.class == Class::Assign { value } else {
+      let mut oper = oper;
+      oper.text.pop();
+
+      Expr::MathOper { span: self.span(target.span().start), lhs: target.clone().wrap(), oper, rhs: value.wrap() }
+    };
+
+    return Node::ChangeVal { span: self.span(target.span().start), target, value }
   }
   fn parse_import_pkg(&mut self) -> Node {
-    self.advance(); let mut path = vec![
+    let start = self.grab().span.start;
+    let mut path = vec![
       self.consume(Class::Identifier, "expected package name.")
     ];
 
@@ -349,32 +620,202 @@ impl Parser {
       );
     }
 
-    return Node::ImportLib { path };
+    return Node::ImportLib { path, span: self.span(start) };
   }
   fn parse_emit_value(&mut self) -> Node {
-    self.advance(); return Node::EmitValue { 
-      value: self.expect_expr() 
-    };
+    let start = self.grab().span.start;
+    let value = self.expect_expr();
+
+    return Node::EmitValue { value, span: self.span(start) };
+  }
+
+  fn parse_while_loop(&mut self) -> Node {
+    let start = self.grab().span.start;
+    let cond = self.restricted(true, Self::expect_expr);
+    let body = self.parse_body().wrap();
+
+    return Node::WhileLoop { cond, body, span: self.span(start) };
+  }
+  fn parse_for_loop(&mut self) -> Node {
+    let start = self.grab().span.start;
+    let item = self.consume(Class::Identifier, "expected loop variable name");
+
+    let token = self.consume(Class::Keyword, "expected 'in' after loop variable");
+    if token.text != "in" {
+      self.error("unexpected keyword", "expected 'in' after loop variable", &token);
+    }
+
+    let iter = self.restricted(true, Self::expect_expr);
+    let body = self.parse_body().wrap();
+
+    return Node::ForLoop { item, iter, body, span: self.span(start) };
   }
 
   fn parse_body(&mut self) -> Node {
+    return self.restricted(false, Self::parse_block);
+  }
+  fn parse_block(&mut self) -> Node {
+    let start = self.current().span.start;
     let mut body = vec![];
     self.consume(Class::LeftBrack, "expected '{' to begin body node.");
 
     loop {
       match self.current().class {
-        Class::Eof => { self.error("unterminated block", "expected body delim '}', found <eof>", &self.current()) },
+        Class::Eof => { self.error("unterminated block", "expected body delim '}', found <eof>", self.current()); break; },
         Class::RightBrack => { self.advance(); break; },
         _ => body.push(self.parse_node())
       }
     };
 
-    if body.len() == 0 {
-      return Node::Compound { value: vec![
-        Node::Expression { expr: Expr::NullVoid { prev: self.tokenth(-1) } }
-      ] }
+    if body.is_empty() {
+      return Node::Compound { value: vec![self.nothing()], span: self.span(start) }
+    }
+
+    return Node::Compound { value: body, span: self.span(start) };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(source: &str) -> (Vec<Node>, Vec<String>) {
+    let (nodes, _, errors) = Parser::init(Lexer::new(Logger::new("test.baf".into(), source.into()))).parse();
+    return (nodes, errors);
+  }
+  fn errors(source: &str) -> Vec<String> {
+    return parse(source).1;
+  }
+  // the lone expression in `source`, with every operation in parentheses.
+  fn shape(source: &str) -> String {
+    fn render(expr: &Expr) -> String {
+      return match expr {
+        Expr::Number { value, .. } | Expr::VarRef { value, .. } | Expr::Boolean { value, .. } => value.text.clone(),
+        Expr::Unary { oper, value, .. } => format!("({}{})", oper.text, render(value)),
+        Expr::MathOper { lhs, oper, rhs, .. }
+          | Expr::BoolOper { lhs, oper, rhs, .. }
+          | Expr::Chained { lhs, stich: oper, rhs, .. } => format!("({} {} {})", render(lhs), oper.text, render(rhs)),
+        Expr::Index { parent, index, .. } => format!("{}[{}]", render(parent), render(index)),
+        Expr::Attribute { parent, attr, .. } => format!("{}:{}", render(parent), render(attr)),
+        _ => "?".into(),
+      };
+    }
+
+    let (nodes, errors) = parse(&format!("{source};"));
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let expr = if let [Node::Expression { expr, .. }] = nodes.as_slice() { expr } else { unreachable!() };
+    return render(expr);
+  }
+
+  #[test]
+  fn the_least_int_is_folded_into_its_literal() {
+    let (nodes, found) = parse("-9223372036854775808;");
+
+    assert!(found.is_empty());
+    assert!(matches!(&nodes[0], Node::Expression { expr: Expr::Number { value, .. }, .. } if value.text == "-9223372036854775808"));
+
+    for source in ["9223372036854775808;", "1 - 9223372036854775808;"] {
+      assert_eq!(errors(source).len(), 1, "{source}");
     }
+  }
+
+  #[test]
+  fn operators_bind_by_precedence() {
+    assert_eq!(shape("1 + 2 * 3"), "(1 + (2 * 3))");
+    assert_eq!(shape("1 * 2 + 3 % 4"), "((1 * 2) + (3 % 4))");
+    assert_eq!(shape("a < b == c > d"), "((a < b) == (c > d))");
+    assert_eq!(shape("a | b ^ c & d"), "(a | (b ^ (c & d)))");
+    assert_eq!(shape("1 + 2 < 3 & x"), "(((1 + 2) < 3) & x)");
+  }
+
+  #[test]
+  fn operators_of_a_level_associate_left() {
+    assert_eq!(shape("1 - 2 - 3"), "((1 - 2) - 3)");
+    assert_eq!(shape("8 / 4 / 2"), "((8 / 4) / 2)");
+    assert_eq!(shape("a & b & c"), "((a & b) & c)");
+  }
 
-    return Node::Compound { value: body };
+  #[test]
+  fn prefix_operators_bind_tighter_than_infix_ones() {
+    assert_eq!(shape("-a * b"), "((-a) * b)");
+    assert_eq!(shape("!a & b"), "((!a) & b)");
+    assert_eq!(shape("- -a"), "(-(-a))");
+    assert_eq!(shape("-+a"), "(-(+a))");
+    assert_eq!(shape("1 - -2"), "(1 - (-2))");
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn prefix_operators_bind_looser_than_indexing() {
+    assert_eq!(shape("-a[0]"), "(-a[0])");
+    assert_eq!(shape("!a:b"), "(!a:b)");
+  }
+
+  #[test]
+  fn every_bad_statement_is_reported() {
+    let errors = errors("set a = ;\nset b = ;\nset c = ;\nset d = ;\n");
+    assert_eq!(errors.len(), 4);
+
+    for (line, error) in errors.iter().enumerate() {
+      assert!(error.contains(&format!("[{}:9]", line + 1)), "{error}");
+    }
+  }
+
+  #[test]
+  fn a_missing_semicolon_does_not_swallow_the_next_statement() {
+    let errors = errors("set a = 1\nset b = 2;\nset c = ;\n");
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors[1].contains("[3:9]"), "{}", errors[1]);
+  }
+
+  #[test]
+  fn doc_comments_belong_to_the_declaration_after_them() {
+    let (nodes, errors) = parse("## a\n## b\nset a = 1;\n## c\nvar c = 1;\n## t\ntype T { x: int };\n# d\nset d = 1;\n## e\n1;\nset e = 1;");
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let docs = nodes.iter().map(|x| match x {
+      Node::SetAssign { doc, .. } | Node::VarAssign { doc, .. } | Node::DeclareType { doc, .. } => doc.clone(),
+      _ => None,
+    }).collect::<Vec<_>>();
+
+    assert_eq!(docs, [Some("a\nb".into()), Some("c".into()), Some("t".into()), None, None, None]);
+  }
+
+  #[test]
+  fn spans_cover_the_source_they_came_from() {
+    let source = "set  名前 = \"é\" +\n  f(1,  2);\nemit   [名前][0];";
+    let (nodes, errors) = parse(source);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let text = |spot: Span| &source[spot.start..spot.end];
+
+    assert_eq!(text(nodes[0].span()), "set  名前 = \"é\" +\n  f(1,  2)");
+    assert_eq!(text(nodes[1].span()), "emit   [名前][0]");
+
+    let (lhs, rhs) = match &nodes[0] {
+      Node::SetAssign { value: Expr::MathOper { lhs, rhs, .. }, .. } => (lhs, rhs),
+      _ => unreachable!(),
+    };
+
+    assert_eq!(text(lhs.span()), "\"é\"");
+    assert_eq!(text(rhs.span()), "f(1,  2)");
+  }
+
+  #[test]
+  fn compound_assignments_change_their_target() {
+    let (nodes, found) = parse("xs[0] *= 2 + 1;");
+    assert!(found.is_empty(), "{found:?}");
+
+    let (target, value) = if let [Node::ChangeVal { target, value, .. }] = nodes.as_slice() { (target, value) } else { unreachable!() };
+    let (lhs, oper, rhs) = if let Expr::MathOper { lhs, oper, rhs, .. } = value { (lhs, oper, rhs) } else { unreachable!() };
+
+    assert!(matches!(target, Expr::Index { .. }));
+    assert_eq!(lhs.span(), target.span());
+    assert_eq!(oper.text, "*");
+    assert!(matches!(**rhs, Expr::MathOper { .. }));
+
+    assert_eq!(errors("f(x += 1);").len(), 1);
+    assert_eq!(errors("1 + x -= 1;").len(), 1);
+  }
+}
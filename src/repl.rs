@@ -0,0 +1,214 @@
+use std::io::{self, Write as _};
+
+use crate::checker::Checker;
+use crate::lexer::Lexer;
+use crate::logger::Logger;
+use crate::parser::Parser;
+use crate::runtime::{Runtime, Value};
+use crate::syntax::Node;
+use crate::utils::Color;
+
+const FILENAME: &str = "<repl>";
+
+pub struct Repl {
+  checker: Checker,
+  runtime: Runtime,
+}
+
+// how many more brackets, braces and parens are opened than closed, ignoring
+// any inside of strings or comments. an unclosed block comment counts as one.
+fn depth(source: &str) -> isize {
+  let mut chars = source.chars();
+  let mut depth = 0;
+
+  while let Some(char) = chars.next() {
+    match char {
+      '{' | '[' | '(' => depth += 1,
+      '}' | ']' | ')' => depth -= 1,
+      '#' if chars.as_str().starts_with('[') => {
+        let mut nested = 1;
+        chars.next();
+
+        while nested > 0 {
+          let rest = chars.as_str();
+          if rest.is_empty() { return depth + 1; }
+
+          if rest.starts_with("#[") { nested += 1; chars.next(); }
+          else if rest.starts_with("]#") { nested -= 1; chars.next(); }
+
+          chars.next();
+        }
+      },
+      '#' => while chars.next().is_some_and(|x| x != '\n') {},
+      '"' => while let Some(char) = chars.next() {
+        if char == '\\' { chars.next(); }
+        if char == '"' { break; }
+      },
+      _ => (),
+    }
+  }
+
+  return depth;
+}
+
+impl Repl {
+  pub fn init() -> Self {
+    let logger = Logger::new(FILENAME.into(), String::new());
+
+    return Repl { checker: Checker::init(Box::new(logger.clone())), runtime: Runtime::session(logger) };
+  }
+  pub fn start(mut self) {
+    println!("baffler repl, enter `:quit` to leave.");
+
+    while let Some(source) = self.read() {
+      let source = source.trim();
+
+      let (command, rest) = source.split_once(' ').unwrap_or((source, ""));
+      let rest = rest.trim();
+
+      match command {
+        "" => (),
+        ":quit" => break,
+        ":scope" => println!("{}", self.runtime.scope()),
+        ":type" => self.measure(rest),
+        ":load" => match std::fs::read_to_string(rest) {
+          Ok(source) => self.run(rest, source),
+          Err(err) => println!("{}: {err}", "error".color(31)),
+        },
+        _ if command.starts_with(':') => {
+          println!("{}: unknown command {command:?}, expected :type, :scope, :load or :quit.", "error".color(31));
+        },
+        _ => self.run(FILENAME, source.to_string()),
+      }
+    }
+  }
+
+  // reads lines until every bracket opened has been closed, or none on eof.
+  fn read(&self) -> Option<String> {
+    let mut source = String::new();
+
+    loop {
+      print!("{}", if source.is_empty() { ">> " } else { ".. " });
+      io::stdout().flush().unwrap();
+
+      let mut line = String::new();
+
+      match io::stdin().read_line(&mut line) {
+        Ok(0) => {
+          println!();
+          return if source.is_empty() { None } else { Some(source) };
+        },
+        Ok(_) => source += &line,
+        Err(err) => {
+          println!("{}: {err}", "error".color(31));
+          return None;
+        },
+      }
+
+      if depth(&source) <= 0 { return Some(source); }
+    }
+  }
+
+  // a lone statement or expression may leave off its trailing semi-colon.
+  fn parse(&self, filename: &str, source: String) -> Option<(Vec<Node>, Box<Logger>)> {
+    let parse = |source: String| {
+      Parser::init(Lexer::new(Logger::new(filename.into(), source))).parse()
+    };
+
+    let (nodes, logger, errors) = parse(source.clone());
+
+    if errors.is_empty() {
+      return Some((nodes, logger));
+    }
+
+    let (nodes, logger, fixed) = parse(format!("{source};"));
+    if fixed.is_empty() { return Some((nodes, logger)) }
+
+    errors.iter().for_each(|x| println!("{x}"));
+    return None;
+  }
+  fn run(&mut self, filename: &str, source: String) {
+    let (nodes, logger) = if let Some(parsed) = self.parse(filename, source) { parsed } else { return };
+    let scope = self.checker.snapshot();
+    let errors = self.checker.extend(logger.clone(), &nodes);
+
+    if !errors.is_empty() {
+      errors.iter().for_each(|x| println!("{x}"));
+      return;
+    }
+
+    // the runtime forgets what a failed line declared, so the checker must too.
+    match self.runtime.evaluate(&nodes, *logger) {
+      Ok(Value::NullVoid) => (),
+      Ok(value) => println!("{value}"),
+      Err(err) => {
+        self.checker.restore(scope);
+        println!("{err}");
+      },
+    }
+  }
+  fn measure(&mut self, source: &str) {
+    let (nodes, logger) = if let Some(parsed) = self.parse(FILENAME, source.to_string()) { parsed } else { return };
+
+    let expr = if let [Node::Expression { expr, .. }] = nodes.as_slice() { expr } else {
+      println!("{}: :type expects a single expression.", "error".color(31));
+      return;
+    };
+
+    match self.checker.measure(logger, expr) {
+      Ok(Some(kind)) => println!("{kind}"),
+      Ok(None) => println!("unknown"),
+      Err(errors) => errors.iter().for_each(|x| println!("{x}")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn input_continues_until_every_bracket_is_closed() {
+    assert_eq!(depth("set f = {} -> int {"), 1);
+    assert_eq!(depth("disp([1, (2)]);"), 0);
+    assert_eq!(depth("disp(\"{[(\"); # {"), 0);
+    assert_eq!(depth("#[ {"), 1);
+    assert_eq!(depth("#[ #[ ]# { ]# }"), -1);
+  }
+
+  #[test]
+  fn a_lone_line_may_leave_off_its_semicolon() {
+    let repl = Repl::init();
+
+    assert!(repl.parse(FILENAME, "1 + 2".into()).is_some());
+    assert!(repl.parse(FILENAME, "set a = 1".into()).is_some());
+    assert!(repl.parse(FILENAME, "set a = ".into()).is_none());
+  }
+
+  #[test]
+  fn lines_with_errors_declare_nothing() {
+    let mut repl = Repl::init();
+
+    let mut check = |source: &str| {
+      let (nodes, logger) = repl.parse(FILENAME, source.into()).unwrap();
+      repl.checker.extend(logger, &nodes).len()
+    };
+
+    assert_eq!(check("set a = 1; set b = nope;"), 1);
+    assert_eq!(check("a;"), 1);
+    assert_eq!(check("set a = 2;"), 0);
+    assert_eq!(check("a + 1;"), 0);
+  }
+
+  #[test]
+  fn lines_that_fail_when_run_declare_nothing() {
+    let mut repl = Repl::init();
+
+    repl.run(FILENAME, "set x = 1 / 0;".into());
+    repl.run(FILENAME, "set x = 5;".into());
+
+    let (nodes, logger) = repl.parse(FILENAME, "x + 1;".into()).unwrap();
+    assert!(repl.checker.extend(logger.clone(), &nodes).is_empty());
+    assert!(repl.runtime.evaluate(&nodes, *logger).is_ok_and(|x| x.to_string() == "6"));
+  }
+}
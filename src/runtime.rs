@@ -1,38 +1,92 @@
-use std::borrow::Borrow as _;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::OnceLock;
+use num_bigint::{BigInt, Sign};
+use ordermap::OrderMap;
 use serde::Serialize;
 
+use crate::checker::Checker;
+use crate::decimal::Decimal;
+use crate::compiler::{Action, Bind, Compiler, Place, Proto, Site, Step};
+use crate::lexer::Lexer;
 use crate::logger::Logger;
 use crate::parser::Parser;
-use crate::syntax::{Expr, Node};
+use crate::syntax::Node;
 use crate::token::Token;
-use crate::utils::{Color as _, Coords, Wrapper};
+use crate::utils::{Color as _, Spanned, Wrapper};
 
-type PrimeFunc = fn(&Box<Logger>, Vec<Value>, &Vec<Expr>) -> Value;
+pub type PrimeFunc = fn(&Logger, Vec<Value>, &[Site]) -> Result<Value, String>;
+// a binding shared between the frame that defines it and every closure that captures it.
+type Cell = Rc<RefCell<Option<Symbol>>>;
 
 #[derive(Clone, PartialEq, Eq, Serialize)]
 pub enum Type {
   String,
   Number,
+  Integer,
+  BigInt,
+  Decimal,
   Boolean,
   NullVoid,
 
   Object(HashMap<String, Type>),
+  Struct(String, OrderMap<String, Type>),
   Array(Box<Type>),
+  Function(Vec<Type>, Box<Type>),
 }
 
 #[derive(Clone, PartialEq, Serialize)]
 pub enum Value {
   String(String),
   Number(f64),
+  Integer(i64),
+  BigInt(BigInt),
+  Decimal(Decimal),
   Boolean(bool),
   NullVoid,
 
-  Object(HashMap<String, Value>),
-  Array(Vec<Value>),
+  // shared between copies until one of them is changed.
+  Object(Rc<HashMap<String, Value>>),
+  Struct(String, Rc<OrderMap<String, Value>>),
+  Array(Rc<Vec<Value>>),
   TypeRef(Type),
+  #[serde(skip)]
+  Function(Closure),
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Param {
+  kind: Type,
+  default: Option<Value>,
+}
+
+#[derive(Clone)]
+pub struct Closure {
+  proto: Rc<Proto>,
+  args: OrderMap<String, Param>,
+  emmission: Type,
+  captures: Vec<Cell>,
+  globals: Rc<RefCell<Scope>>,
+}
+
+impl Closure {
+  fn as_type(&self) -> Type {
+    let args = self.args.values().map(|x| x.kind.clone()).collect();
+    Type::Function(args, self.emmission.clone().wrap())
+  }
+}
+
+// closures are equal when made from the same function and sharing every
+// binding they capture.
+impl PartialEq for Closure {
+  fn eq(&self, other: &Self) -> bool {
+    let captures = self.captures.iter().zip(&other.captures).all(|(l, r)| Rc::ptr_eq(l, r));
+    return Rc::ptr_eq(&self.proto, &other.proto) && captures;
+  }
 }
 
 impl Value {
@@ -40,6 +94,9 @@ impl Value {
     match self {
       Value::String(_) => Type::String,
       Value::Number(_) => Type::Number,
+      Value::Integer(_) => Type::Integer,
+      Value::BigInt(_) => Type::BigInt,
+      Value::Decimal(_) => Type::Decimal,
       Value::Boolean(_) => Type::Boolean,
       Value::NullVoid => Type::NullVoid,
       Value::Object(attrs) => {
@@ -49,13 +106,29 @@ impl Value {
 
         Type::Object(attrs)
       },
+      Value::Struct(name, attrs) => {
+        let attrs = attrs.iter().map(|(name, value)| {
+          (name.to_string(), value.as_type())
+        }).collect();
+
+        Type::Struct(name.to_string(), attrs)
+      },
+      // nothing is known of what an empty array holds.
       Value::Array(items) => {
-        let parent = items[0].as_type();
+        let parent = items.first().map_or(Type::NullVoid, Value::as_type);
         Type::Array(parent.wrap())
       },
       Value::TypeRef(t) => t.clone(),
+      Value::Function(func) => func.as_type(),
     }
   }
+  // an empty array fits any array type, as nothing in it says otherwise.
+  pub fn fits(&self, kind: &Type) -> bool {
+    return match (self, kind) {
+      (Value::Array(items), Type::Array(_)) if items.is_empty() => true,
+      _ => &self.as_type() == kind,
+    };
+  }
 }
 
 impl Display for Value {
@@ -63,6 +136,9 @@ impl Display for Value {
     let s: String = match self {
       Value::String(value) => value.to_string(),
       Value::Number(value) => value.to_string(),
+      Value::Integer(value) => value.to_string(),
+      Value::BigInt(value) => value.to_string(),
+      Value::Decimal(value) => value.to_string(),
       Value::Boolean(value) => value.to_string(),
       Value::NullVoid => "null".to_string(),
       Value::Object(attrs) => {
@@ -72,6 +148,13 @@ impl Display for Value {
 
         format!("{{ {attrs} }}")
       },
+      Value::Struct(name, attrs) => {
+        let attrs = attrs.iter().map(|(name, value)| {
+          format!("{name}: {value}")
+        }).collect::<Vec<String>>().join(", ");
+
+        format!("{name} {{ {attrs} }}")
+      },
       Value::Array(items) => {
         let items = items.iter().map(|item| {
           item.to_string()
@@ -80,6 +163,7 @@ impl Display for Value {
         format!("[{items}]")
       },
       Value::TypeRef(t) => t.to_string(),
+      Value::Function(func) => func.as_type().to_string(),
     };
 
     write!(f, "{s}")
@@ -91,6 +175,9 @@ impl Display for Type {
     let s: String = match self {
       Type::String => "str".into(),
       Type::Number => "num".into(),
+      Type::Integer => "int".into(),
+      Type::BigInt => "bigint".into(),
+      Type::Decimal => "decimal".into(),
       Type::Boolean => "bool".into(),
       Type::NullVoid => "null".into(),
       Type::Object(attrs) => {
@@ -100,29 +187,33 @@ impl Display for Type {
 
         format!("{{ {attrs} }}")
       },
+      Type::Struct(name, _) => name.to_string(),
       Type::Array(parent) => format!("{parent}[]"),
+      Type::Function(args, emits) => {
+        let args = args.iter().map(|kind| {
+          kind.to_string()
+        }).collect::<Vec<String>>().join(", ");
+
+        format!("({args}) -> {emits}")
+      },
     };
 
     write!(f, "{s}")
   }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone)]
 enum Symbol {
   Variable { value: Value, mutable: bool },
-  Function { args: HashMap<String, Type>, emmission: Type, code: Vec<Node> },
+  Function { func: Closure },
   TypeRefr { parent: Type },
+  Module { scope: Rc<RefCell<Scope>> },
 }
 
 impl Symbol {
   pub fn var(value: Value, mutable: bool) -> Self {
     Self::Variable { value, mutable }
   }
-  pub fn func(args: HashMap<String, Type>, emmission: Type, code: Box<Node>) -> Self {
-    let code = if let Node::Compound { value } = *code 
-      { value } else { vec![*code] };
-    Self::Function { args, emmission, code }
-  }
   pub fn refr(parent: Type) -> Self {
     Self::TypeRefr { parent }
   }
@@ -132,14 +223,15 @@ impl Display for Symbol {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     let s: String = match self {
       Symbol::Variable { value, mutable } => format!("sym:var {{ value: {value}, const: {} }}", !mutable),
-      Symbol::Function { args, emmission, .. } => {
-        let args = args.iter().map(|(_, kind)| {
-          kind.to_string()
+      Symbol::Function { func } => {
+        let args = func.args.values().map(|param| {
+          param.kind.to_string()
         }).collect::<Vec<String>>().join(", ");
 
-        format!("sym:func {{ args: {args}, emits: {emmission} }}")
+        format!("sym:func {{ args: {args}, emits: {} }}", func.emmission)
       }
       Symbol::TypeRefr { parent } => format!("sym:type {{ parent: {parent} }}"),
+      Symbol::Module { scope } => format!("sym:module {{ symbols: {} }}", scope.borrow().symbols.iter().flatten().count()),
     };
 
     write!(f, "{s}")
@@ -147,72 +239,140 @@ impl Display for Symbol {
 }
 
 
+// the globals of a script or module. the compiler gives every name it sees a
+// slot, so they are found by index rather than looked up by name.
 #[derive(Clone)]
-struct Scope {
-  symbols: HashMap<String, Symbol>,
-  parent: Option<Box<Scope>>
+pub struct Scope {
+  names: HashMap<String, usize>,
+  symbols: Vec<Option<Symbol>>,
 }
 
 impl Scope {
-  pub fn init(parent: Scope) -> Scope {
-    let symbols = HashMap::new();
-
-    return Scope { parent: Some(parent.wrap()), symbols };
-  }
-
-  pub fn get<S:ToString>(&self, name: S) -> Option<&Symbol> {
-    if let Some(symbol) = self.symbols.get(&name.to_string()) {
-      return Some(symbol);
+  pub fn slot(&mut self, name: &str) -> usize {
+    if let Some(slot) = self.names.get(name) {
+      return *slot;
     }
 
-    if let Some(parent) = self.parent.borrow() {
-      return parent.get(name.to_string());
-    } else { return None; };
-  }
+    self.symbols.push(None);
+    self.names.insert(name.to_string(), self.symbols.len() - 1);
 
-  pub fn set<S:ToString>(&mut self, name: S, symbol: Symbol) {
-    self.symbols.insert(name.to_string(), symbol);
+    return self.symbols.len() - 1;
+  }
+  fn get(&self, name: &str) -> Option<&Symbol> {
+    return self.symbols[*self.names.get(name)?].as_ref();
+  }
+  fn set(&mut self, name: &str, symbol: Symbol) {
+    let slot = self.slot(name);
+    self.symbols[slot] = Some(symbol);
   }
 }
 impl Display for Scope {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let string = self.symbols.iter().map(|(name, value)| {
-      format!("{{ name: {name}, symbol: {value} }}")
+    let string = self.names.iter().filter_map(|(name, slot)| {
+      let value = self.symbols[*slot].as_ref()?;
+      Some(format!("{{ name: {name}, symbol: {value} }}"))
     }).collect::<Vec<String>>().join("\n");
 
     write!(f, "symbols: \n{string}")
   }
 }
 
+// the types every scope starts out with, for both the checker and the runtime.
+#[allow(non_snake_case)]
+pub fn RuntimeTypes() -> Vec<(String, Type)> {
+  let types = vec![
+    ("str", Type::String),
+    ("num", Type::Number),
+    ("int", Type::Integer),
+    ("bigint", Type::BigInt),
+    ("decimal", Type::Decimal),
+    ("bool", Type::Boolean),
+    ("null", Type::NullVoid),
+  ];
+
+  return types.into_iter().map(|(x, y)| (x.to_string(), y)).collect();
+}
+
 #[allow(non_snake_case)]
-fn RootScope() -> Scope {
-  let symbols = vec![
-    ("str", Symbol::refr(Type::String)),
-    ("num", Symbol::refr(Type::Number)),
-    ("bool", Symbol::refr(Type::Boolean)),
-    ("null", Symbol::refr(Type::NullVoid)),
-  ].into_iter().map(|(x, y)| {
-    (x.to_string(), y)
-  }).collect();
-
-  return Scope {
-    symbols, parent: None
+fn RootScope() -> Rc<RefCell<Scope>> {
+  let mut scope = Scope { names: HashMap::new(), symbols: vec![] };
+
+  for (name, kind) in RuntimeTypes() {
+    scope.set(&name, Symbol::refr(kind));
+  }
+
+  return Rc::new(RefCell::new(scope));
+}
+
+// resolves `a:b:c` to `a/b/c.baf` beside the importing file, falling back to its `lib/`.
+pub fn locate(from: &Path, path: &[Token]) -> Result<PathBuf, String> {
+  let path_s = path.iter().map(|x| {
+    x.text.clone()
+  }).collect::<Vec<String>>().join("/") + ".baf";
+
+  let base = from.parent().map(|x| x.to_path_buf()).unwrap_or_default();
+
+  return [base.join(&path_s), base.join("lib").join(&path_s)].into_iter().find(|x| {
+    x.is_file()
+  }).ok_or(path_s);
+}
+pub fn canon(path: &Path) -> PathBuf {
+  return std::fs::canonicalize(path).unwrap_or(path.to_path_buf());
+}
+// the arguments given to the script after `--`.
+pub static ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+fn numeric(value: &Value) -> bool {
+  return matches!(value, Value::Number(_) | Value::Integer(_) | Value::BigInt(_) | Value::Decimal(_));
+}
+// the exact value of any number but a num.
+fn exact(value: &Value) -> Option<Decimal> {
+  return match value {
+    Value::Integer(value) => Some(Decimal::from(BigInt::from(*value))),
+    Value::BigInt(value) => Some(Decimal::from(value.clone())),
+    Value::Decimal(value) => Some(value.clone()),
+    _ => None,
+  };
+}
+// numbers of any type are equal when they hold the same amount, as they are
+// for `<=` and `>=`.
+fn equal(l: &Value, r: &Value) -> bool {
+  if let (Some(l), Some(r)) = (exact(l), exact(r)) { return l == r }
+
+  return match (l, r) {
+    (Value::Number(n), other) | (other, Value::Number(n)) if exact(other).is_some() => {
+      other.to_string().parse::<f64>().is_ok_and(|x| x == *n)
+    },
+    _ => l == r,
   };
 }
-#[allow(non_snake_case, unused)]
+fn order<T:PartialOrd>(o: &str, l: &T, r: &T) -> bool {
+  return match o {
+    "<=" => l <= r,
+    ">=" => l >= r,
+    "<" => l < r,
+    ">" => l > r,
+    _ => unreachable!(),
+  };
+}
+fn cells(count: usize) -> Vec<Cell> {
+  return (0..count).map(|_| Cell::default()).collect();
+}
+
+#[allow(non_snake_case)]
 pub fn RuntimePrimatives() -> HashMap<String, PrimeFunc>{
   let funcs: Vec<(&str, PrimeFunc)> = vec![
-    ("disp", |_: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
+    ("disp", |_: &Logger, args: Vec<Value>, _: &[Site]| -> Result<Value, String> {
       let args = args.iter().map(|x| {
         x.to_string()
       }).collect::<Vec<String>>();
 
       println!("{}", args.join(", "));
 
-      Value::NullVoid
+      Ok(Value::NullVoid)
     }),
-    ("input", |_: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
-      let msg = if let Some(first) = args.first() 
+    ("input", |_: &Logger, args: Vec<Value>, _: &[Site]| -> Result<Value, String> {
+      let msg = if let Some(first) = args.first()
         { first.to_string() } else { "".into() };
       print!("{msg}");
 
@@ -223,39 +383,129 @@ pub fn RuntimePrimatives() -> HashMap<String, PrimeFunc>{
         println!("{}: could not read input!", "error".color(31))
       };
 
-      return Value::String(input);
+      return Ok(Value::String(input));
     }),
-    ("format", | logger: &Box<Logger>, args: Vec<Value>, exprs: &Vec<Expr>| -> Value {
+    ("format", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
       let msg = if let Some(str) = args.first() { str } else {
-        logger.error("invalid arguments", "format takes in a string", &exprs[0]);
-        return Value::NullVoid;
+        return Err(logger.error("invalid arguments", "format takes in a string", spots[0]));
       };
 
       let msg = if let Value::String(val) = msg { val.to_string() } else {
-        logger.error("invalid arguments", "format takes in a string", &exprs[0]);
-        return Value::NullVoid;
+        return Err(logger.error("invalid arguments", "format takes in a string", spots[0]));
       };
 
       if args.len() == 1 {
-        return Value::String(msg);
+        return Ok(Value::String(msg));
       }
 
       let msg: Vec<&str> = msg.split("{}").collect();
       let mut str: Vec<String> = vec![];
 
       for i in 0..msg.len() - 1 {
-        str.push(format!("{}{}", msg[i], args[i + 1]));
+        let arg = if let Some(arg) = args.get(i + 1) { arg } else {
+          return Err(logger.error("invalid arguments", format!("format expected {} values, but was given {}.", msg.len() - 1, args.len() - 1), spots[0]));
+        };
+
+        str.push(format!("{}{}", msg[i], arg));
       };
 
       str.push(msg.last().unwrap().to_string());
-      return Value::String(str.join(""));
+      return Ok(Value::String(str.join("")));
     }),
-    ("typeof", | _: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
-      let args = args.into_iter().map(|x| { 
+    ("typeof", | _: &Logger, args: Vec<Value>, _: &[Site]| -> Result<Value, String> {
+      let args = args.into_iter().map(|x| {
         Value::TypeRef(x.as_type())
       }).collect::<Vec<Value>>();
 
-      return Value::Array(args);
+      return Ok(Value::Array(Rc::new(args)));
+    }),
+    ("args", | _: &Logger, _: Vec<Value>, _: &[Site]| -> Result<Value, String> {
+      let args = ARGS.get().cloned().unwrap_or_default().into_iter().map(|x| {
+        Value::String(x)
+      }).collect::<Vec<Value>>();
+
+      return Ok(Value::Array(Rc::new(args)));
+    }),
+    ("env", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      let name = if let Some(Value::String(name)) = args.first() { name } else {
+        return Err(logger.error("invalid arguments", "env takes in a string", spots[0]));
+      };
+
+      return Ok(Value::String(std::env::var(name).unwrap_or_default()));
+    }),
+    ("exit", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      let code = if let Some(Value::Integer(code)) = args.first() { *code } else {
+        return Err(logger.error("invalid arguments", "exit takes in an int", spots[0]));
+      };
+
+      // only the low byte of a status reaches the parent, so `exit(256)` would
+      // pass as a success.
+      let code = u8::try_from(code).map_err(|_| {
+        logger.error("invalid arguments", format!("exit codes range from 0 to 255, not {code}."), spots[0])
+      })?;
+
+      std::io::stdout().flush().unwrap();
+      std::process::exit(code as i32);
+    }),
+    ("int", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      let value = match args.first() {
+        Some(Value::Integer(value)) => Some(*value),
+        Some(Value::BigInt(value)) => i64::try_from(value).ok(),
+        Some(Value::Decimal(value)) => i64::try_from(value.trunc()).ok(),
+        Some(Value::Number(value)) if value.is_finite() && value.abs() < i64::MAX as f64 => Some(value.trunc() as i64),
+        Some(Value::String(value)) => value.trim().parse().ok(),
+        Some(Value::Number(_)) => None,
+        _ => return Err(logger.error("invalid arguments", "int takes in a numeric type or str", spots[0])),
+      };
+
+      return value.map(Value::Integer).ok_or_else(|| {
+        logger.error("invalid conversion", format!("{} cannot be converted to an int.", args[0]), spots[0])
+      });
+    }),
+    ("num", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      let value = match args.first() {
+        Some(Value::Integer(value)) => Some(*value as f64),
+        Some(Value::Number(value)) => Some(*value),
+        Some(Value::BigInt(value)) => value.to_string().parse().ok(),
+        Some(Value::Decimal(value)) => value.to_string().parse().ok(),
+        Some(Value::String(value)) => value.trim().parse().ok(),
+        _ => return Err(logger.error("invalid arguments", "num takes in a numeric type or str", spots[0])),
+      };
+
+      return value.map(Value::Number).ok_or_else(|| {
+        logger.error("invalid conversion", format!("{} cannot be converted to a num.", args[0]), spots[0])
+      });
+    }),
+    ("bigint", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      let value = match args.first() {
+        Some(Value::Integer(value)) => Some(BigInt::from(*value)),
+        Some(Value::BigInt(value)) => Some(value.clone()),
+        Some(Value::Decimal(value)) => Some(value.trunc()),
+        Some(Value::Number(value)) if value.is_finite() => format!("{:.0}", value.trunc()).parse().ok(),
+        Some(Value::String(value)) => value.trim().parse().ok(),
+        Some(Value::Number(_)) => None,
+        _ => return Err(logger.error("invalid arguments", "bigint takes in a numeric type or str", spots[0])),
+      };
+
+      return value.map(Value::BigInt).ok_or_else(|| {
+        logger.error("invalid conversion", format!("{} cannot be converted to a bigint.", args[0]), spots[0])
+      });
+    }),
+    ("decimal", | logger: &Logger, args: Vec<Value>, spots: &[Site]| -> Result<Value, String> {
+      // a num goes through its shortest text, so `0.1` stays `0.1`.
+      let value = match args.first() {
+        Some(Value::Integer(value)) => Some(Decimal::from(BigInt::from(*value))),
+        Some(Value::BigInt(value)) => Some(Decimal::from(value.clone())),
+        Some(Value::Decimal(value)) => Some(value.clone()),
+        Some(Value::Number(value)) if value.is_finite() => Decimal::parse(&value.to_string()),
+        Some(Value::String(value)) => Decimal::parse(value.trim()),
+        Some(Value::Number(_)) => None,
+        _ => return Err(logger.error("invalid arguments", "decimal takes in a numeric type or str", spots[0])),
+      };
+
+      return value.map(Value::Decimal).ok_or_else(|| {
+        logger.error("invalid conversion", format!("{} cannot be converted to a decimal.", args[0]), spots[0])
+      });
     }),
   ];
 
@@ -265,605 +515,1427 @@ pub fn RuntimePrimatives() -> HashMap<String, PrimeFunc>{
 
   return funcs;
 }
+// the builtin functions, made once and shared from then on.
+pub fn primitives() -> &'static HashMap<String, PrimeFunc> {
+  static FUNCS: OnceLock<HashMap<String, PrimeFunc>> = OnceLock::new();
+  return FUNCS.get_or_init(RuntimePrimatives);
+}
+
+pub struct Error {
+  message: String,
+  trace: Vec<String>,
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.trace.is_empty() {
+      return write!(f, "{}", self.message);
+    }
+
+    write!(f, "{}\nstack trace:\n{}", self.message, self.trace.join("\n"))
+  }
+}
+
+struct Call {
+  label: String,
+  site: String,
+  spot: Site,
+}
+
+struct Frame {
+  proto: Rc<Proto>,
+  closure: Option<Closure>,
+  globals: Rc<RefCell<Scope>>,
+  slots: Vec<Cell>,
+  marks: Vec<usize>,
+  base: usize,
+  ip: usize,
+  call: Option<Call>,
+}
+
+enum Key {
+  Field(String),
+  Index(usize),
+}
 
 pub struct Runtime {
-  scope: Scope,
-  nodes: Vec<Node>,
-  logger: Box<Logger>,
+  script: Rc<Proto>,
+  globals: Rc<RefCell<Scope>>,
+  frames: Vec<Frame>,
+  stack: Vec<Value>,
+  modules: HashMap<PathBuf, Rc<RefCell<Scope>>>,
+  importing: Vec<PathBuf>,
+}
+
+// parses and type checks a script, exiting with every error found if it has any.
+pub fn verify(parser: Parser) -> (Vec<Node>, Box<Logger>) {
+  let (nodes, logger, errors) = parser.parse();
+
+  if !errors.is_empty() {
+    errors.iter().for_each(|x| println!("{x}"));
+    println!("{}: aborting due to {} syntax error(s).", "error".color(31), errors.len());
+    std::process::exit(1);
+  }
+
+  let (logger, errors) = Checker::init(logger).check(&nodes);
+
+  if !errors.is_empty() {
+    errors.iter().for_each(|x| println!("{x}"));
+    println!("{}: aborting due to {} type error(s).", "error".color(31), errors.len());
+    std::process::exit(1);
+  }
+
+  return (nodes, logger);
 }
 
 impl Runtime {
   pub fn init(parser: Parser) -> Self {
-    let (nodes, logger) = parser.parse();
-    let scope = RootScope();
+    let (nodes, logger) = verify(parser);
 
-    return Self { scope, nodes, logger };
+    let importing = vec![PathBuf::from(logger.filename())];
+    let globals = RootScope();
+    let script = Compiler::init(Rc::new(*logger), globals.clone()).compile(&nodes);
+
+    return Self {
+      script, globals, frames: vec![], stack: vec![],
+      modules: HashMap::new(), importing,
+    };
   }
-  pub fn interperate(mut self) {
-    self.nodes.clone().into_iter().for_each(|x| {
-      self.compute(x);
-    });
+  pub fn session(logger: Logger) -> Self {
+    let importing = vec![PathBuf::from(logger.filename())];
+    let globals = RootScope();
+    let script = Compiler::init(Rc::new(logger), globals.clone()).compile(&[]);
+
+    return Self {
+      script, globals, frames: vec![], stack: vec![],
+      modules: HashMap::new(), importing,
+    };
   }
+  pub fn interperate(mut self) -> Result<(), Error> {
+    let slots = cells(self.script.slots);
+    self.enter(self.script.clone(), None, self.globals.clone(), slots, None);
 
-  fn lookup<S:ToString>(&self, name: S) -> Option<&Symbol> {
-    return self.scope.get(name.to_string());
+    return self.execute(0);
   }
-  fn insert<S:ToString>(&mut self, name: S, symbol: Symbol) {
-    return self.scope.set(name, symbol);
+  // runs more code against the same globals, giving back the value of a
+  // trailing expression, or null. code that fails declares nothing, though
+  // what it changed before failing stays changed.
+  pub fn evaluate(&mut self, nodes: &[Node], logger: Logger) -> Result<Value, Error> {
+    self.importing = vec![PathBuf::from(logger.filename())];
+    let declared = self.globals.borrow().symbols.iter().map(Option::is_some).collect::<Vec<bool>>();
+
+    let proto = Compiler::init(Rc::new(logger), self.globals.clone()).interactive(nodes);
+    self.enter(proto.clone(), None, self.globals.clone(), cells(proto.slots), None);
+
+    if let Err(err) = self.execute(0) {
+      let symbols = &mut self.globals.borrow_mut().symbols;
+
+      for (slot, symbol) in symbols.iter_mut().enumerate() {
+        if !declared.get(slot).copied().unwrap_or(false) { *symbol = None }
+      }
+
+      return Err(err);
+    }
+
+    return Ok(self.pop());
+  }
+  pub fn scope(&self) -> String {
+    return self.globals.borrow().to_string();
   }
 
-  fn enter(&mut self) {
-    self.scope = Scope::init(self.scope.clone());
+  fn enter(&mut self, proto: Rc<Proto>, closure: Option<Closure>, globals: Rc<RefCell<Scope>>, slots: Vec<Cell>, call: Option<Call>) {
+    let base = self.stack.len();
+    self.frames.push(Frame { proto, closure, globals, slots, marks: vec![], base, ip: 0, call });
   }
-  fn leave(&mut self) {
-    if let Some(parent) = self.scope.parent.take() {
-      self.scope = *parent;
+  fn execute(&mut self, floor: usize) -> Result<(), Error> {
+    while self.frames.len() > floor {
+      let frame = self.frames.last_mut().unwrap();
+      let (proto, ip) = (frame.proto.clone(), frame.ip);
+      frame.ip += 1;
+
+      if let Err(err) = self.step(&proto.code[ip]) {
+        if let Some(frame) = self.frames.get(floor) {
+          self.stack.truncate(frame.base);
+        }
+
+        self.frames.truncate(floor);
+        return Err(err);
+      }
     }
+
+    return Ok(());
+  }
+
+  fn frame(&mut self) -> &mut Frame {
+    return self.frames.last_mut().unwrap();
+  }
+  fn pop(&mut self) -> Value {
+    return self.stack.pop().unwrap();
+  }
+  fn fetch(&self, place: &Place) -> Option<Symbol> {
+    let frame = self.frames.last().unwrap();
+
+    return match place {
+      Place::Local(slot) => frame.slots[*slot].borrow().clone(),
+      Place::Capture(index) => frame.closure.as_ref().unwrap().captures[*index].borrow().clone(),
+      Place::Global(slot) => frame.globals.borrow().symbols[*slot].clone(),
+      Place::Own => frame.closure.clone().map(|func| Symbol::Function { func }),
+    };
+  }
+  fn store(&mut self, place: &Place, symbol: Symbol) {
+    let frame = self.frame();
+
+    match place {
+      Place::Global(slot) => frame.globals.borrow_mut().symbols[*slot] = Some(symbol),
+      place => *self.cell(place).borrow_mut() = Some(symbol),
+    }
+  }
+  fn cell(&self, place: &Place) -> Cell {
+    let frame = self.frames.last().unwrap();
+
+    return match place {
+      Place::Local(slot) => frame.slots[*slot].clone(),
+      Place::Capture(index) => frame.closure.as_ref().unwrap().captures[*index].clone(),
+      Place::Own => Rc::new(RefCell::new(self.fetch(place))),
+      Place::Global(_) => unreachable!(),
+    };
   }
 
-  pub fn error<S:ToString, V:ToString, C:Coords>(&self, header: S, message: V, spot: C) {
-    println!("{}", self.logger.error(header, message.to_string(), spot));
+  // the calls that led to the current frame, innermost first.
+  fn trace(&self) -> Vec<String> {
+    return self.frames.iter().rev().filter_map(|frame| {
+      let call = frame.call.as_ref()?;
+      Some(format!("  in {:?}, called at {}", call.label, call.site))
+    }).collect();
+  }
+  pub fn error<S:ToString, V:ToString, C:Spanned>(&self, header: S, message: V, spot: C) -> Error {
+    let logger = &self.frames.last().unwrap().proto.logger;
+    return Error { message: logger.error(header, message.to_string(), spot), trace: self.trace() };
   }
 }
 
 impl Runtime {
-  fn evaluate(&mut self, expr: Expr) -> Value {
-    let value: Value = match expr.clone() {
-      Expr::String { value } => Value::String(value.text),
-      Expr::Number { value } => Value::Number(value.text.parse().unwrap()),
-      Expr::Boolean { value } => Value::Boolean(value.text.parse().unwrap()),
-      Expr::VarRef { value } => {
-        let res = if let Some(sym) = self.lookup(&value.text) { sym } else {
-          self.error("symbol does not exist", format!("{:?} could not be resolved", value.text), value);
-          return Value::NullVoid;
-        };
+  fn step(&mut self, action: &Action) -> Result<(), Error> {
+    match action {
+      Action::Push(value) => self.stack.push(value.clone()),
+      Action::Pop => { self.pop(); },
+      Action::Jump(target) => self.frame().ip = *target,
+      Action::JumpIfNot { target, spot } => {
+        let value = self.pop();
+        if !self.truthy(value, *spot)? { self.frame().ip = *target }
+      },
+      Action::Mark => {
+        let height = self.stack.len();
+        self.frame().marks.push(height);
+      },
+      Action::Unmark => { self.frame().marks.pop(); },
+      Action::Exit { depth, leave, carry, target } => {
+        let value = if *carry { Some(self.pop()) } else { None };
+        let frame = self.frame();
 
+        let len = frame.marks.len() - depth;
+        frame.marks.truncate(len);
 
-        match res {
-          Symbol::Variable { value, .. } => value.clone(),
-          Symbol::Function { .. } => {
-            self.insert(value.text, res.clone());
-            Value::NullVoid
-          },
-          Symbol::TypeRefr { .. } => {
-            self.error("invalid reference", format!("{:?} is a type, not a value.", value.text), value);
-            Value::NullVoid
-          },
-        }
+        let height = if *leave { frame.marks.pop() } else { frame.marks.last().copied() }.unwrap();
+        frame.ip = *target;
+
+        self.stack.truncate(height);
+        self.stack.extend(value);
+      },
+      Action::Clear { from, to } => {
+        // every iteration gets fresh bindings, so closures made in one keep theirs.
+        let slots = &mut self.frame().slots;
+        slots.splice(*from..*to, cells(to - from));
       },
-      Expr::FunCall { name, args } => {
-        let args = match self.is_default_function(&name.text, args) {
-          Ok(value) => return value,
-          Err(args) => args,
-        };
 
-        let res = if let Some(symbol) = self.lookup(&name.text) { symbol } else {
-          self.error("symbol does not exist", format!("{:?} could not be resolved", name.text), name);
-          return Value::NullVoid;
+      Action::Load { place, name, spot, callee } => {
+        let symbol = if let Some(symbol) = self.fetch(place) { symbol } else {
+          return Err(self.error("symbol does not exist", format!("{name:?} could not be resolved"), spot));
         };
 
-        let (params, emits, code) = match res {
-          Symbol::Function { args, emmission, code } => ( args.clone(), emmission.clone(), code.clone() ),
-          _ => {
-            self.error("invalid operation", format!("{:?} is not a function", &name.text), name);
-            return Value::NullVoid
+        let value = match symbol {
+          Symbol::Variable { value: value @ Value::Function(_), .. } => value,
+          Symbol::Function { func } => Value::Function(func),
+          _ if *callee => {
+            return Err(self.error("invalid operation", format!("{name:?} is not a function"), spot));
           },
+          symbol => self.value(symbol, name, *spot)?,
         };
 
-        if &params.len() != &args.len() {
-          self.error("arguments differ in length", format!("{:?} expected {} args, but was given {}.", &name.text, params.len(), args.len()), name);
-          return Value::NullVoid;
-        }
-
-        let pars: Vec<&Type> = params.values().collect();
-        let pnms: Vec<&String> = params.keys().collect();
-
-        self.enter();
-
-        for i in 0..args.len() {
-          let x = &args[i];
-          let y = self.evaluate(x.clone());
-
-          if pars[i] != &y.as_type() {
-            self.error("mismatched types", format!("{:?} expected {}, but was given {}.", &name.text, pars[i], y.as_type()), name);
-            return Value::NullVoid;
-          }
+        self.stack.push(value);
+      },
+      Action::Define { place, outer, bind, name, spot } => {
+        let value = self.pop();
 
-          self.insert(pnms[i], Symbol::Variable { value: y, mutable: true });
+        if self.fetch(place).is_some() || outer.as_ref().is_some_and(|x| self.fetch(x).is_some()) {
+          return Err(self.error("symbol already exists", format!("{name:?} has already been defined."), spot));
         }
 
-        let emmission = self.run(Node::Compound { value: code });
-
-        self.leave();
-
-        if emmission.as_type() != emits {
-          self.error("mismatched types", format!("{:?} expected to emit {}, but emits {}.", &name.text, emits, emmission.as_type()), name);
-        }
+        let symbol = match (bind, value) {
+          (Bind::Variable(mutable), value) => Symbol::var(value, *mutable),
+          (Bind::Function, Value::Function(func)) => Symbol::Function { func },
+          (Bind::TypeRefr, Value::TypeRef(parent)) => Symbol::refr(parent),
+          _ => unreachable!(),
+        };
 
-        emmission
+        self.store(place, symbol);
+      },
+      Action::Store { place, name, steps, spot, root } => self.modify(place, name, steps, *spot, *root)?,
+      Action::Path { place, path } => {
+        let value = self.path(place, path)?;
+        self.stack.push(value);
       },
-      Expr::Object { attrs } => {
-        let mut fields = HashMap::new();
-        
-        for attr in attrs {
+      Action::Import { path, place, outer } => self.import(path, place, outer)?,
 
-          let (name, value) = if let Expr::ObjectField { name, attr } = attr {
-            (name.text, self.evaluate(*attr))
-          } else if let Expr::NullVoid { .. } = attr {
-            return Value::Object(fields);
-          } else { unreachable!() };
-          
-          fields.insert(name, value);
-        }
+      Action::Array { spots } => {
+        let items = self.stack.split_off(self.stack.len() - spots.len());
+
+        if let Some(first) = items.first().map(Value::as_type) {
+          for (item, spot) in items.iter().zip(spots) {
+            let kind = item.as_type();
 
-        Value::Object(fields)
-      },
-      Expr::ObjectField { attr, .. } => self.evaluate(*attr),
-      Expr::Array { value } => {
-        let first = self.evaluate(value[0].clone()).as_type();
-        
-        let items = value.iter().map(|expr| {
-          let item = self.evaluate(expr.clone());
-          let kind = item.as_type();
-          
-          if kind != first {
-            self.error("mismatched types", format!("found {kind} in {first}[]."), expr);
+            if kind != first {
+              return Err(self.error("mismatched types", format!("found {kind} in {first}[]."), spot));
+            }
           }
+        }
 
-          item
-        }).collect::<Vec<Value>>();
+        self.stack.push(Value::Array(Rc::new(items)));
+      },
+      Action::Object { names } => {
+        let values = self.stack.split_off(self.stack.len() - names.len());
+        let fields = names.iter().cloned().zip(values).collect();
 
-        Value::Array(items)
+        self.stack.push(Value::Object(Rc::new(fields)));
       },
-      Expr::Index { parent, index } => {
-        let from = self.evaluate(*parent);
-        let index = *index;
+      Action::Construct { place, space, name, fields, spot } => {
+        let values = self.stack.split_off(self.stack.len() - fields.len());
+        let value = self.construct(place, space, name, fields, values, *spot)?;
 
-        let indx = if let Value::Number(num) = self.evaluate(index.clone()) {
-          if num.fract() == 0.0 && num >= 0.0 { num as usize } else {
-            self.error("invalid expression", format!("cannot perform index with a non positive integer."), index);
-            return Value::NullVoid;
-          }
-        } else {
-          self.error("invalid expression", format!("cannot perform index with a non positive integer."), index);
-          return Value::NullVoid;
-        };
-        
+        self.stack.push(value);
+      },
+      Action::Index { spot, index } => {
+        let indx = self.pop();
+        let from = self.pop();
+        let indx = self.index(indx, *index)?;
 
-        match from {
+        let value = match from {
           Value::String(value) => {
-            if indx >= value.len() {
-              self.error("invalid expression", format!("index out of bounds of parent."), &expr);
-              return Value::NullVoid;
-            }
+            let char = if let Some(char) = value.chars().nth(indx) { char } else {
+              return Err(self.error("invalid expression", "index out of bounds of parent.", spot));
+            };
 
-            Value::String((value.as_bytes()[indx] as char).to_string())
+            Value::String(char.to_string())
           },
           Value::Array(value) => {
             if indx >= value.len() {
-              self.error("invalid expression", format!("index out of bounds of parent."), &expr);
-              return Value::NullVoid;
+              return Err(self.error("invalid expression", "index out of bounds of parent.", spot));
             }
-            return value[indx].clone();
+            value[indx].clone()
           },
           _ => {
-            self.error("invalid operation", format!("cannot perform indedx upon {}", from.as_type()), &expr);
-            Value::NullVoid
+            return Err(self.error("invalid operation", format!("cannot perform indedx upon {}", from.as_type()), spot));
           },
-        }
+        };
+
+        self.stack.push(value);
       },
-      Expr::Lambda { .. } => { Value::NullVoid },
-      Expr::IfExpr { cond, body, other } => {
-        let e = self.evaluate(*cond.clone());
+      Action::Attr { name, spot } => {
+        let parent = self.pop();
+        let value = self.attribute(parent, name, *spot)?;
 
-        let condition: bool = match e {
-          Value::String(value) => value.len() != 0,
-          Value::Number(value) => value >= 0.0,
-          Value::Boolean(value) => value,
-          Value::NullVoid => false,
-          _ => {
-            self.error("invalid expression", format!("{} cannot be evaluated to a boolean.", e.as_type()), &*cond);
-            false
-          },
-        };
+        self.stack.push(value);
+      },
+
+      Action::Unary { oper, spot } => {
+        let value = self.pop();
+        let value = self.unary(oper, value, *spot)?;
 
-        if condition { self.compute(*body) } else { self.compute(*other) }
+        self.stack.push(value);
       },
-      Expr::BoolOper { lhs, oper, rhs } => {
-        let l = self.evaluate(*lhs);
-        let r = self.evaluate(*rhs);
-        let o = oper.text.as_str();
+      Action::Compare { oper, spot } => {
+        let r = self.pop();
+        let l = self.pop();
+        let value = self.compare(oper, l, r, *spot)?;
 
-        if o == "==" { return Value::Boolean(l == r) }
-        if o == "!=" { return Value::Boolean(r != l) }
+        self.stack.push(value);
+      },
+      Action::Math { oper, spot } => {
+        let r = self.pop();
+        let l = self.pop();
+        let value = self.math(oper, l, r, *spot)?;
 
-        let l: f64 = match l {
-          Value::String(value) => value.len() as f64,
-          Value::Number(value) => value,
-          Value::Array(value) => value.len() as f64,
-          _ => {
-            self.error("invalid operation", format!("{o:?} is a numeric exclusive comparison operator."), oper);
-            return Value::Boolean(false);
-          },
-        };
-        let r: f64 = match r {
-          Value::String(value) => value.len() as f64,
-          Value::Number(value) => value,
-          Value::Array(value) => value.len() as f64,
-          _ => {
-            self.error("invalid operation", format!("{o:?} is a numeric exclusive comparison operator."), oper);
-            return Value::Boolean(false);
-          },
+        self.stack.push(value);
+      },
+      Action::Chain { oper, spot } => {
+        let r = self.pop();
+        let l = self.pop();
+
+        let (l, r) = match (l, r) {
+          (Value::Boolean(l), Value::Boolean(r)) => (l, r),
+          _ => return Err(self.error("invalid operation", "cannot chain non-boolean values.", spot)),
         };
 
-        let res: bool = match o {
-          "<=" => l <= r,
-          ">=" => l >= r,
-          "<" => l < r,
-          ">" => l > r,
-          _ => unreachable!(),
+        let res: bool = match oper.as_str() {
+          "|" => l || r,
+          "&" => l && r,
+          "^" => l ^ r,
+          _ => unreachable!()
         };
 
-        return Value::Boolean(res);
+        self.stack.push(Value::Boolean(res));
       },
-      Expr::MathOper { lhs, oper, rhs } => {
-        let l = self.evaluate(*lhs.clone());
-        let r = self.evaluate(*rhs);
-        let o = oper.text.clone();
+      Action::Interpolate { count } => {
+        let parts = self.stack.split_off(self.stack.len() - count);
+        let value = parts.iter().map(|x| x.to_string()).collect::<String>();
 
-        match l.as_type() {
-          Type::String 
-            | Type::Number 
-            | Type::Array(_) => (),
-          _ => {
-            self.error("invalid operation", format!("cannot perform {o:?} upon a {}", l.as_type()), &*lhs);
-            return l;
-          },
-        }
+        self.stack.push(Value::String(value));
+      },
 
-        if o == "+" || o == "+=" {
-          match l.clone() {
-            Value::String(value) => {
-              return Value::String(value + &r.to_string());
-            },
-            Value::Number(value) => {
-              if let Value::Number(num) = r {
-                return Value::Number(value + num);
-              } else {
-                self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), &*lhs);
-                return Value::NullVoid;
-              }
-            },
-            Value::Array(value) => {
-              match r.clone() {
-                Value::Array(of) => {
-                  if r.as_type() == l.as_type() {
-                    return Value::Array([value, of].concat());
-                  } else {
-                    self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), &*lhs);
-                    return Value::NullVoid;
-                  }
-                },
-                _ => {
-                  if value[0].as_type() == Type::NullVoid {
-                    return Value::Array(vec![r]);
-                  } else if value[0].as_type() == r.as_type() {
-                    return Value::Array(vec![value, vec![r]].concat());
-                  } else {
-                    self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), &*lhs);
-                    return Value::NullVoid;
-                  }
-                },
-              }
-            },
-            _ => unreachable!()
-          }
+      Action::TypeRef { place, space, name, arrs } => {
+        let mut parent = if let Symbol::TypeRefr { parent } = self.symbol(place, space, name)? { parent } else {
+          return Err(self.error("invalid reference", format!("{:?} is not a type", name.0), name.1));
+        };
+
+        for _ in 0..*arrs {
+          parent = Type::Array(parent.wrap())
         }
 
-        let r = if let Value::Number(num) = r { num } else {
-          self.error("invalid operation", format!("{o:?} is an exclusive numeric operation."), &*lhs);
-          return l;
+        self.stack.push(Value::TypeRef(parent));
+      },
+      Action::FunType { args } => {
+        let emits = match self.pop() {
+          Value::TypeRef(t) => t,
+          _ => Type::NullVoid,
         };
 
-        let l = if let Value::Number(num) = l { num } else {
-          self.error("invalid operation", format!("{o:?} is an exclusive numeric operation."), &*lhs);
-          return l;
-        };
+        let types = self.stack.split_off(self.stack.len() - args).into_iter().filter_map(|x| {
+          if let Value::TypeRef(t) = x { Some(t) } else { None }
+        }).collect();
 
-        let res = match oper.text.as_str() {
-          "-" | "-=" => l - r,
-          "*" | "*=" => l * r,
-          "/" | "/=" => l / r,
-          "%" | "%=" => l % r,
-          _ => unreachable!(),
-        };
+        self.stack.push(Value::TypeRef(Type::Function(types, emits.wrap())));
+      },
+      Action::Struct { name, fields } => {
+        let kinds = self.stack.split_off(self.stack.len() - fields.len());
 
-        return Value::Number(res);
+        let fields = fields.iter().cloned().zip(kinds).map(|(name, kind)| {
+          if let Value::TypeRef(t) = kind { (name, t) } else { unreachable!() }
+        }).collect();
+
+        self.stack.push(Value::TypeRef(Type::Struct(name.clone(), fields)));
+      },
+      Action::Closure { proto } => {
+        let value = self.closure(proto.clone())?;
+        self.stack.push(value);
       },
-      Expr::Chained { lhs, stich, rhs } => {
-        let l = self.evaluate(*lhs);
-        let r = self.evaluate(*rhs);
 
-        let l = if let Value::Boolean(val) = l { val } else {
-          self.error("invalid operation", format!("cannot chain non-boolean values."), expr);
-          return Value::Boolean(false);
-        };
-        let r = if let Value::Boolean(val) = r { val } else {
-          self.error("invalid operation", format!("cannot chain non-boolean values."), expr);
-          return Value::Boolean(false);
-        };
+      Action::Call { label, names, spots, spot } => {
+        let args = self.stack.split_off(self.stack.len() - names.len());
+        let callee = self.pop();
 
-        let s = stich.text.as_str();
+        self.call(label, *spot, callee, args, names, spots)?;
+      },
+      Action::Native { func, count, spots } => {
+        let args = self.stack.split_off(self.stack.len() - count);
+        let logger = self.frames.last().unwrap().proto.logger.clone();
 
-        let res: bool = match s {
-          "|" => l || r,
-          "&" => l && r,
-          _ => unreachable!()
-        };
+        let value = func(&logger, args, spots).map_err(|message| {
+          Error { message, trace: self.trace() }
+        })?;
+
+        self.stack.push(value);
+      },
+      Action::Return => {
+        let value = self.pop();
+        let frame = self.frames.last().unwrap();
 
-        return Value::Boolean(res);
+        if let (Some(call), Some(func)) = (&frame.call, &frame.closure) {
+          if !value.fits(&func.emmission) {
+            return Err(self.error("mismatched types", format!("{:?} expected to emit {}, but emits {}.", call.label, func.emmission, value.as_type()), call.spot));
+          }
+        }
+
+        let frame = self.frames.pop().unwrap();
+        self.stack.truncate(frame.base);
+        self.stack.push(value);
       },
-      Expr::TypeRef { base, arrs } => {
-        let res = if let Some(symbol) = self.lookup(&base.text) { symbol } else {
-          self.error("symbol does not exist", format!("{:?} could not be resolved", base.text), base);
-          return Value::NullVoid;
+
+      Action::Items { spot } => {
+        let items: Vec<Value> = match self.pop() {
+          Value::Array(items) => items.to_vec(),
+          Value::String(value) => value.chars().map(|x| Value::String(x.to_string())).collect(),
+          Value::Object(attrs) => {
+            let mut keys = attrs.keys().cloned().collect::<Vec<String>>();
+            keys.sort();
+            keys.into_iter().map(Value::String).collect()
+          },
+          Value::Struct(_, attrs) => attrs.keys().cloned().map(Value::String).collect(),
+          other => {
+            return Err(self.error("invalid operation", format!("cannot iterate over {}.", other.as_type()), spot));
+          },
         };
 
-        let mut parent = if let Symbol::TypeRefr { parent } = res { parent.clone() } else {
-          self.error("invalid reference", format!("{:?} is not a type", base.text), base);
-          return Value::NullVoid;
+        self.stack.push(Value::Array(Rc::new(items)));
+        self.stack.push(Value::Integer(0));
+      },
+      Action::Next { target } => {
+        let len = self.stack.len();
+
+        let (items, indx) = match &self.stack[len - 2..] {
+          [Value::Array(items), Value::Integer(indx)] => (items, *indx as usize),
+          _ => unreachable!(),
         };
 
-        for _ in 0..arrs {
-          parent = Type::Array(parent.wrap())
+        match items.get(indx).cloned() {
+          Some(item) => {
+            self.stack[len - 1] = Value::Integer(indx as i64 + 1);
+            self.stack.push(item);
+          },
+          None => self.frame().ip = *target,
         }
+      },
+      Action::Raise { header, message, spot } => return Err(self.error(header, message, spot)),
+    }
+
+    return Ok(());
+  }
 
-        return Value::TypeRef(parent);
+  fn value(&self, symbol: Symbol, name: &str, spot: Site) -> Result<Value, Error> {
+    return match symbol {
+      Symbol::Variable { value, .. } => Ok(value),
+      Symbol::Function { func } => Ok(Value::Function(func)),
+      Symbol::TypeRefr { .. } => {
+        Err(self.error("invalid reference", format!("{name:?} is a type, not a value."), spot))
+      },
+      Symbol::Module { .. } => {
+        Err(self.error("invalid reference", format!("{name:?} is a module, not a value."), spot))
       },
-      _ => Value::NullVoid
     };
+  }
+  fn symbol(&self, place: &Place, space: &[(String, Site)], name: &(String, Site)) -> Result<Symbol, Error> {
+    let mut scope: Option<Rc<RefCell<Scope>>> = None;
 
-    return value;
+    for (part, spot) in space {
+      let symbol = match &scope {
+        Some(scope) => scope.borrow().get(part).cloned(),
+        None => self.fetch(place),
+      };
+
+      scope = match symbol {
+        Some(Symbol::Module { scope }) => Some(scope),
+        Some(_) => return Err(self.error("invalid reference", format!("{part:?} is not a module."), spot)),
+        None => return Err(self.error("symbol does not exist", format!("{part:?} could not be resolved"), spot)),
+      };
+    }
+
+    let symbol = match scope {
+      Some(scope) => scope.borrow().get(&name.0).cloned(),
+      None => self.fetch(place),
+    };
+
+    return symbol.ok_or_else(|| {
+      self.error("symbol does not exist", format!("{:?} could not be resolved", name.0), name.1)
+    });
   }
-  fn is_default_function(&mut self, name: &String, args: Vec<Expr>) -> Result<Value, Vec<Expr>> {
-    let funcs: Vec<(&str, PrimeFunc)> = vec![
-      ("disp", |_: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
-        let args = args.iter().map(|x| {
-          x.to_string()
-        }).collect::<Vec<String>>();
+  fn path(&self, place: &Place, path: &[(String, Site)]) -> Result<Value, Error> {
+    let (root, spot) = &path[0];
 
-        println!("{}", args.join(", "));
+    let symbol = if let Some(symbol) = self.fetch(place) { symbol } else {
+      return Err(self.error("symbol does not exist", format!("{root:?} could not be resolved"), spot));
+    };
 
-        Value::NullVoid
-      }),
-      ("input", |_: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
-        let msg = if let Some(first) = args.first() 
-          { first.to_string() } else { "".into() };
-        print!("{msg}");
+    let mut scope = if let Symbol::Module { scope } = symbol { scope } else {
+      let mut value = self.value(symbol, root, *spot)?;
 
-        std::io::stdout().flush().unwrap();
-        let mut input = String::new();
+      for (name, spot) in &path[1..] {
+        value = self.attribute(value, name, *spot)?;
+      }
 
-        if std::io::stdin().read_line(&mut input).is_err() {
-          println!("{}: could not read input!", "error".color(31))
-        };
+      return Ok(value);
+    };
+
+    let (name, spot) = path.last().unwrap();
+
+    for (part, spot) in &path[1..path.len() - 1] {
+      let symbol = scope.borrow().get(part).cloned();
+
+      scope = match symbol {
+        Some(Symbol::Module { scope }) => scope,
+        Some(_) => return Err(self.error("invalid reference", format!("{part:?} is not a module."), spot)),
+        None => return Err(self.error("symbol does not exist", format!("{part:?} could not be resolved"), spot)),
+      };
+    }
+
+    let symbol = scope.borrow().get(name).cloned();
+
+    return match symbol {
+      Some(symbol) => self.value(symbol, name, *spot),
+      None => {
+        let space = &path[path.len() - 2].0;
+        Err(self.error("symbol does not exist", format!("{name:?} could not be resolved in {space:?}"), spot))
+      },
+    };
+  }
+  fn attribute(&self, parent: Value, name: &str, spot: Site) -> Result<Value, Error> {
+    return match &parent {
+      Value::Object(attrs) if attrs.contains_key(name) => Ok(attrs[name].clone()),
+      Value::Struct(_, attrs) if attrs.contains_key(name) => Ok(attrs[name].clone()),
+      Value::Object(_) | Value::Struct(..) => {
+        Err(self.error("invalid attribute", format!("{name:?} is not a field of {}.", parent.as_type()), spot))
+      },
+      other => {
+        Err(self.error("invalid operation", format!("cannot access attribute {name:?} upon {}.", other.as_type()), spot))
+      },
+    };
+  }
+  fn construct(&self, place: &Place, space: &[(String, Site)], name: &(String, Site), fields: &[(String, Site, Site)], values: Vec<Value>, spot: Site) -> Result<Value, Error> {
+    let kinds = match self.symbol(place, space, name)? {
+      Symbol::TypeRefr { parent: Type::Struct(_, fields) } => fields,
+      _ => return Err(self.error("invalid reference", format!("{:?} is not a struct type.", name.0), name.1)),
+    };
+
+    let mut given = HashMap::new();
+
+    for ((field, at, attr), value) in fields.iter().zip(values) {
+      let kind = if let Some(kind) = kinds.get(field) { kind } else {
+        return Err(self.error("invalid field", format!("{field:?} is not a field of {}.", name.0), at));
+      };
+
+      if given.contains_key(field) {
+        return Err(self.error("invalid field", format!("{field:?} was given more than once."), at));
+      }
+
+      if !value.fits(kind) {
+        return Err(self.error("mismatched types", format!("{}:{field} expected {kind}, but was given {}.", name.0, value.as_type()), attr));
+      }
+
+      given.insert(field.clone(), value);
+    }
+
+    let missing = kinds.keys().filter(|x| !given.contains_key(*x)).map(|x| {
+      format!("{x:?}")
+    }).collect::<Vec<String>>();
+
+    if !missing.is_empty() {
+      return Err(self.error("missing fields", format!("{} is missing {}.", name.0, missing.join(", ")), spot));
+    }
 
-        return Value::String(input);
-      }),
-      ("format", | logger: &Box<Logger>, args: Vec<Value>, exprs: &Vec<Expr>| -> Value {
-        let msg = if let Some(str) = args.first() { str } else {
-          logger.error("invalid arguments", "format takes in a string", &exprs[0]);
-          return Value::NullVoid;
+    let attrs = kinds.keys().map(|x| {
+      (x.clone(), given.remove(x).unwrap())
+    }).collect();
+
+    return Ok(Value::Struct(name.0.clone(), Rc::new(attrs)));
+  }
+  fn index(&self, index: Value, spot: Site) -> Result<usize, Error> {
+    return match index {
+      Value::Integer(num) if num >= 0 => Ok(num as usize),
+      Value::Integer(_) => Err(self.error("invalid expression", "cannot perform index with a negative int.", spot)),
+      other => Err(self.error("invalid expression", format!("cannot perform index with {}.", other.as_type()), spot)),
+    };
+  }
+  fn truthy(&self, value: Value, spot: Site) -> Result<bool, Error> {
+    return match value {
+      Value::String(value) => Ok(!value.is_empty()),
+      Value::Number(value) => Ok(value >= 0.0),
+      Value::Integer(value) => Ok(value >= 0),
+      Value::BigInt(value) => Ok(value.sign() != Sign::Minus),
+      Value::Decimal(value) => Ok(!value.is_negative()),
+      Value::Boolean(value) => Ok(value),
+      Value::NullVoid => Ok(false),
+      _ => Err(self.error("invalid expression", format!("{} cannot be evaluated to a boolean.", value.as_type()), spot)),
+    };
+  }
+  fn unary(&self, oper: &str, value: Value, spot: Site) -> Result<Value, Error> {
+    return match (oper, value) {
+      ("-", Value::Number(num)) => Ok(Value::Number(-num)),
+      ("+", Value::Number(num)) => Ok(Value::Number(num)),
+      ("-", Value::Integer(num)) => self.checked(num.checked_neg(), oper, spot),
+      ("+", Value::Integer(num)) => Ok(Value::Integer(num)),
+      ("-", Value::BigInt(num)) => Ok(Value::BigInt(-num)),
+      ("+", Value::BigInt(num)) => Ok(Value::BigInt(num)),
+      ("-", Value::Decimal(num)) => Ok(Value::Decimal(num.neg())),
+      ("+", Value::Decimal(num)) => Ok(Value::Decimal(num)),
+      ("!", Value::Boolean(val)) => Ok(Value::Boolean(!val)),
+      (o, v) => {
+        let expects = if o == "!" { Type::Boolean } else { Type::Number };
+        Err(self.error("mismatched types", format!("unary {o:?} expects {expects}, but was given {}.", v.as_type()), spot))
+      },
+    };
+  }
+  fn compare(&self, o: &str, l: Value, r: Value, spot: Site) -> Result<Value, Error> {
+    if o == "==" { return Ok(Value::Boolean(equal(&l, &r))) }
+    if o == "!=" { return Ok(Value::Boolean(!equal(&l, &r))) }
+
+    // ints, bigints and decimals are compared exactly, as large or precise
+    // ones do not fit in a num.
+    if let (Some(l), Some(r)) = (exact(&l), exact(&r)) {
+      return Ok(Value::Boolean(order(o, &l, &r)));
+    }
+
+    let l: f64 = match l {
+      Value::String(value) => value.chars().count() as f64,
+      Value::Number(value) => value,
+      Value::Integer(value) => value as f64,
+      Value::BigInt(value) => value.to_string().parse().unwrap(),
+      Value::Decimal(value) => value.to_string().parse().unwrap(),
+      Value::Array(value) => value.len() as f64,
+      _ => {
+        return Err(self.error("invalid operation", format!("{o:?} is a numeric exclusive comparison operator."), spot));
+      },
+    };
+    let r: f64 = match r {
+      Value::String(value) => value.chars().count() as f64,
+      Value::Number(value) => value,
+      Value::Integer(value) => value as f64,
+      Value::BigInt(value) => value.to_string().parse().unwrap(),
+      Value::Decimal(value) => value.to_string().parse().unwrap(),
+      Value::Array(value) => value.len() as f64,
+      _ => {
+        return Err(self.error("invalid operation", format!("{o:?} is a numeric exclusive comparison operator."), spot));
+      },
+    };
+
+    return Ok(Value::Boolean(order(o, &l, &r)));
+  }
+  fn math(&self, o: &str, l: Value, r: Value, spot: Site) -> Result<Value, Error> {
+    match l.as_type() {
+      Type::String
+        | Type::Number
+        | Type::Integer
+        | Type::BigInt
+        | Type::Decimal
+        | Type::Array(_) => (),
+      _ => {
+        return Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {}", l.as_type()), spot));
+      },
+    }
+
+    if o == "+" {
+      return match l.clone() {
+        Value::String(value) => {
+          Ok(Value::String(value + &r.to_string()))
+        },
+        Value::Number(value) => {
+          if let Value::Number(num) = r {
+            Ok(Value::Number(value + num))
+          } else {
+            Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+          }
+        },
+        Value::Integer(value) => {
+          if let Value::Integer(num) = r {
+            self.checked(value.checked_add(num), o, spot)
+          } else {
+            Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+          }
+        },
+        Value::BigInt(value) => {
+          if let Value::BigInt(num) = r {
+            Ok(Value::BigInt(value + num))
+          } else {
+            Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+          }
+        },
+        Value::Decimal(value) => {
+          if let Value::Decimal(num) = r {
+            Ok(Value::Decimal(value.add(&num)))
+          } else {
+            Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+          }
+        },
+        Value::Array(value) => {
+          match r.clone() {
+            Value::Array(of) => {
+              if l.fits(&r.as_type()) || r.fits(&l.as_type()) {
+                Ok(Value::Array(Rc::new([value.as_slice(), of.as_slice()].concat())))
+              } else {
+                Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+              }
+            },
+            _ => {
+              if value.first().is_none_or(|x| x.as_type() == r.as_type()) {
+                let mut value = value;
+                Rc::make_mut(&mut value).push(r);
+                Ok(Value::Array(value))
+              } else {
+                Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+              }
+            },
+          }
+        },
+        _ => unreachable!()
+      }
+    }
+
+    return match (l, r) {
+      (Value::Number(l), Value::Number(r)) => {
+        let res = match o {
+          "-" => l - r,
+          "*" => l * r,
+          "/" => l / r,
+          "%" => l % r,
+          _ => unreachable!(),
         };
 
-        let msg = if let Value::String(val) = msg { val.to_string() } else {
-          logger.error("invalid arguments", "format takes in a string", &exprs[0]);
-          return Value::NullVoid;
+        Ok(Value::Number(res))
+      },
+      // ints divide towards zero, and the remainder takes the sign of `l`.
+      (Value::Integer(l), Value::Integer(r)) => {
+        if r == 0 && matches!(o, "/" | "%") {
+          return Err(self.error("invalid operation", format!("cannot perform {o:?} by zero."), spot));
+        }
+
+        let res = match o {
+          "-" => l.checked_sub(r),
+          "*" => l.checked_mul(r),
+          "/" => l.checked_div(r),
+          "%" => l.checked_rem(r),
+          _ => unreachable!(),
         };
 
-        if args.len() == 1 {
-          return Value::String(msg);
+        self.checked(res, o, spot)
+      },
+      (Value::BigInt(l), Value::BigInt(r)) => {
+        if r.sign() == Sign::NoSign && matches!(o, "/" | "%") {
+          return Err(self.error("invalid operation", format!("cannot perform {o:?} by zero."), spot));
         }
 
-        let msg: Vec<&str> = msg.split("{}").collect();
-        let mut str: Vec<String> = vec![];
+        let res = match o {
+          "-" => l - r,
+          "*" => l * r,
+          "/" => l / r,
+          "%" => l % r,
+          _ => unreachable!(),
+        };
 
-        for i in 0..msg.len() - 1 {
-          str.push(format!("{}{}", msg[i], args[i + 1]));
+        Ok(Value::BigInt(res))
+      },
+      (Value::Decimal(l), Value::Decimal(r)) => {
+        let res = match o {
+          "-" => Some(l.sub(&r)),
+          "*" => Some(l.mul(&r)),
+          "/" => l.div(&r),
+          "%" => l.rem(&r),
+          _ => unreachable!(),
         };
 
-        str.push(msg.last().unwrap().to_string());
-        return Value::String(str.join(""));
-      }),
-      ("typeof", | _: &Box<Logger>, args: Vec<Value>, _: &Vec<Expr>| -> Value {
-        let args = args.into_iter().map(|x| { 
-          Value::TypeRef(x.as_type())
-        }).collect::<Vec<Value>>();
-
-        return Value::Array(args);
-      }),
-    ];
-
-    let funcs = funcs.into_iter().map(|(name, func)| {
-      (name.to_string(), func)
-    }).collect::<HashMap<String, PrimeFunc>>();
-
-    
-    if let Some(func) = funcs.get(name) {
-      let pargs: Vec<Value> = args.iter().map(
-        |x| self.evaluate(x.clone()
-      )).collect();
-      return Ok(func(&self.logger, pargs, &args));
-    } else {
-      return Err(args);
+        res.map(Value::Decimal).ok_or_else(|| {
+          self.error("invalid operation", format!("cannot perform {o:?} by zero."), spot)
+        })
+      },
+      (l, r) if numeric(&l) && numeric(&r) => {
+        Err(self.error("invalid operation", format!("cannot perform {o:?} upon a {} with a {}.", l.as_type(), r.as_type()), spot))
+      },
+      _ => Err(self.error("invalid operation", format!("{o:?} is an exclusive numeric operation."), spot)),
     };
   }
+  fn checked(&self, value: Option<i64>, o: &str, spot: Site) -> Result<Value, Error> {
+    return value.map(Value::Integer).ok_or_else(|| {
+      self.error("integer overflow", format!("{o:?} overflowed the bounds of an int."), spot)
+    });
+  }
 }
 
 impl Runtime {
-  fn compute(&mut self, node: Node) -> Value {
-    let mut emmission = Value::NullVoid;
-
-    match &node {
-      Node::SetAssign { .. } => self.assign(node), 
-      Node::VarAssign { .. } => self.assign(node),
-      Node::ChangeVal { .. } => self.modify(node),
-      Node::ImportLib { .. } => self.import(node),
-      Node::EmitValue { .. } => emmission = self.emit(node),
-      Node::DeclareType { .. } => self.create_type(node),
-      Node::Compound { .. } => emmission = self.run(node),
-      Node::Expression { .. } => emmission = self.expression(node),
+  fn closure(&mut self, proto: Rc<Proto>) -> Result<Value, Error> {
+    let emmission = match self.pop() {
+      Value::TypeRef(t) => t,
+      _ => Type::NullVoid,
     };
 
-    return emmission;
-  }
+    let mut params = vec![];
 
-  fn assign(&mut self, node: Node) {
-    let (name, value, mutable) = match node {
-      Node::SetAssign { name, value } => {
-        let value = if let Expr::Lambda { args, kind, body } = value {
-          self.fundef(name, args, kind, body); return;
-        } else { self.evaluate(value) };
+    for arg in proto.args.iter().rev() {
+      let default = arg.default.map(|spot| (self.pop(), spot));
 
-        (name, value, false)
-      },
-      Node::VarAssign { name, value } => {
-        let value = if let Expr::Lambda { args, kind, body } = value {
-          self.fundef(name, args, kind, body); return;
-        } else { self.evaluate(value) };
-        
-        (name, value, true)
+      let kind = if let Value::TypeRef(t) = self.pop()
+        { t } else { unreachable!() };
+
+      if let Some((value, spot)) = &default {
+        if !value.fits(&kind) {
+          return Err(self.error("mismatched types", format!("{:?} is {kind}, but its default is {}.", arg.name, value.as_type()), spot));
+        }
+      }
+
+      params.push((arg.name.clone(), Param { kind, default: default.map(|x| x.0) }));
+    }
+
+    let args = params.into_iter().rev().collect();
+    let captures = proto.captures.iter().map(|x| self.cell(x)).collect();
+    let globals = self.frame().globals.clone();
+
+    return Ok(Value::Function(Closure { proto, args, emmission, captures, globals }));
+  }
+  fn call(&mut self, label: &str, spot: Site, callee: Value, args: Vec<Value>, names: &[Option<(String, Site)>], spots: &[Site]) -> Result<(), Error> {
+    let func = match callee {
+      Value::Function(func) => func,
+      other => {
+        return Err(self.error("invalid operation", format!("{} is not a function", other.as_type()), spot));
       },
-      _ => unreachable!()
     };
 
-    if self.lookup(&name.text).is_some() {
-      self.error("symbol already exists", format!("{:?} has already been defined.", name.text), name);
-      return;
+    let given = names.iter().filter(|x| x.is_none()).count();
+
+    if given > func.args.len() {
+      return Err(self.error("arguments differ in length", format!("{label:?} expected at most {} args, but was given {given}.", func.args.len()), spot));
     }
 
-    self.insert(name.text, Symbol::var(value, mutable));
-  }
-  fn fundef(&mut self, name: Token, args: Vec<Expr>, kind: Box<Expr>, body: Box<Node>) {
-    if self.lookup(&name.text).is_some() {
-      self.error("symbol already exists", format!("{:?} has already been defined.", name.text), name);
-      return;
+    let mut bound: OrderMap<String, Value> = OrderMap::new();
+    let mut named = false;
+
+    for (i, (value, name)) in args.into_iter().zip(names).enumerate() {
+      let name = match name {
+        Some((name, at)) => {
+          named = true;
+
+          if !func.args.contains_key(name) {
+            return Err(self.error("invalid argument", format!("{label:?} has no parameter named {name:?}."), at));
+          }
+          if bound.contains_key(name) {
+            return Err(self.error("invalid argument", format!("{name:?} was given more than once."), at));
+          }
+
+          name.clone()
+        },
+        None => {
+          if named {
+            return Err(self.error("invalid argument", "positional arguments cannot follow named arguments.", spots[i]));
+          }
+
+          func.args.get_index(i).unwrap().0.clone()
+        },
+      };
+
+      let kind = &func.args[&name].kind;
+
+      if !value.fits(kind) {
+        return Err(self.error("mismatched types", format!("{label:?} expected {kind} for {name:?}, but was given {}.", value.as_type()), spots[i]));
+      }
+
+      bound.insert(name, value);
     }
 
-    let params = args.into_iter().map(|expr| {
-      let (name, kind) = if let Expr::TypePair { name, kind } = expr {
-        (name, kind.clone())
-      } else { unreachable!() };
-      let kind = if let Value::TypeRef(t) = self.evaluate(*kind) 
-        { t } else { unreachable!() };
+    let slots = cells(func.proto.slots);
 
-      (name.text.clone(), kind)
-    }).collect::<HashMap<String, Type>>();
+    for (i, (name, param)) in func.args.iter().enumerate() {
+      let value = match (bound.remove(name), &param.default) {
+        (Some(value), _) => value,
+        (None, Some(value)) => value.clone(),
+        (None, None) => {
+          return Err(self.error("missing argument", format!("{label:?} expected a value for {name:?}."), spot));
+        },
+      };
 
-    let kind = match self.evaluate(*kind) {
-      Value::TypeRef(t) => t,
-      Value::NullVoid => Type::NullVoid,
-      _ => unreachable!()
-    };
-    
-    self.insert(name.text, Symbol::func(params, kind, body));
+      *slots[i].borrow_mut() = Some(Symbol::var(value, true));
+    }
+
+    let logger = &self.frames.last().unwrap().proto.logger;
+    let [line, col] = logger.locate(spot.start);
+    let site = format!("{}[{line}:{col}]", logger.filename());
+    let call = Call { label: label.to_string(), site, spot };
+
+    self.enter(func.proto.clone(), Some(func.clone()), func.globals.clone(), slots, Some(call));
+    return Ok(());
   }
-  fn modify(&mut self, node: Node) {
-    let (name, value) = if let Node::ChangeVal { name, value } = node {
-      (name, self.evaluate(value))
-    } else { unreachable!() };
+  fn modify(&mut self, place: &Place, name: &str, steps: &[Step], spot: Site, root: Site) -> Result<(), Error> {
+    let count = steps.iter().filter(|x| matches!(x, Step::Index(_))).count();
+    let mut indexes = self.stack.split_off(self.stack.len() - count).into_iter();
+    let value = self.pop();
+
+    let mut keys = vec![];
+
+    for step in steps {
+      keys.push(match step {
+        Step::Field(name, at) => (Key::Field(name.clone()), *at),
+        Step::Index(at) => (Key::Index(self.index(indexes.next().unwrap(), *at)?), *at),
+      });
+    }
 
-    let symbol = if let Some(res) = self.lookup(&name.text) { res } else {
-      self.error("symbol does not exist", format!("{:?} has could not be resolved.", name.text), name);
-      return;
+    let symbol = if let Some(res) = self.fetch(place) { res } else {
+      return Err(self.error("symbol does not exist", format!("{name:?} has could not be resolved."), root));
     };
 
-    let (kind, mutable) = match symbol {
-      Symbol::Variable { value, mutable } => (value.as_type(), mutable),
+    let (mut slot, mutable) = match symbol {
+      Symbol::Variable { value, mutable } => (value, mutable),
       Symbol::Function { .. } => {
-        self.error("invalid operation", format!("{:?} is a function which cannot be assigned to a value.", name.text), name);
-        return;
+        return Err(self.error("invalid operation", format!("{name:?} is a function which cannot be assigned to a value."), root));
       },
       Symbol::TypeRefr { .. } => {
-        self.error("invalid operation", format!("{:?} is a type reference which cannot be assigned to a value.", name.text), name);
-        return;
+        return Err(self.error("invalid operation", format!("{name:?} is a type reference which cannot be assigned to a value."), root));
+      },
+      Symbol::Module { .. } => {
+        return Err(self.error("invalid operation", format!("{name:?} is a module which cannot be assigned to a value."), root));
       },
     };
 
     if !mutable {
-      self.error("invalid operation", format!("{:?} is a constant and cannot be reassigned.", name.text), name);
-      return;
+      return Err(self.error("invalid operation", format!("{name:?} is a constant and cannot be reassigned."), root));
+    }
+
+    let label = keys.iter().fold(name.to_string(), |label, (key, _)| match key {
+      Key::Field(attr) => format!("{label}:{attr}"),
+      Key::Index(indx) => format!("{label}[{indx}]"),
+    });
+
+    // the binding lets go of its value first, so that it is changed in place
+    // rather than copied.
+    self.store(place, Symbol::var(Value::NullVoid, true));
+    let placed = self.place(&mut slot, &keys, value, (&label, spot));
+    self.store(place, Symbol::var(slot, true));
+
+    return placed;
+  }
+  fn place(&self, slot: &mut Value, keys: &[(Key, Site)], value: Value, target: (&str, Site)) -> Result<(), Error> {
+    let ((key, spot), rest) = if let Some(first) = keys.split_first() { first } else {
+      if !value.fits(&slot.as_type()) && !slot.fits(&value.as_type()) {
+        return Err(self.error("invalid operation", format!("{:?} has been assigned to be {}, not {}", target.0, slot.as_type(), value.as_type()), target.1));
+      }
+
+      *slot = value;
+      return Ok(());
+    };
+
+    let kind = slot.as_type();
+
+    let next = match (key, slot) {
+      (Key::Field(name), Value::Struct(_, attrs)) => match Rc::make_mut(attrs).get_mut(name) {
+        Some(next) => next,
+        None => return Err(self.error("invalid attribute", format!("{name:?} is not a field of {kind}."), spot)),
+      },
+      (Key::Field(name), Value::Object(attrs)) => match Rc::make_mut(attrs).get_mut(name) {
+        Some(next) => next,
+        None => return Err(self.error("invalid attribute", format!("{name:?} is not a field of {kind}."), spot)),
+      },
+      (Key::Index(indx), Value::Array(items)) => match Rc::make_mut(items).get_mut(*indx) {
+        Some(next) => next,
+        None => return Err(self.error("invalid expression", "index out of bounds of parent.", spot)),
+      },
+      (Key::Field(name), _) => {
+        return Err(self.error("invalid operation", format!("cannot assign attribute {name:?} upon {kind}."), spot));
+      },
+      (Key::Index(_), _) => {
+        return Err(self.error("invalid operation", format!("cannot assign index upon {kind}."), spot));
+      },
+    };
+
+    return self.place(next, rest, value, target);
+  }
+  fn import(&mut self, path: &[Token], place: &Place, outer: &Option<Place>) -> Result<(), Error> {
+    let file = match locate(self.importing.last().unwrap(), path) {
+      Ok(file) => file,
+      Err(path_s) => return Err(self.error("invalid path", format!("{path_s} is not a valid filepath."), path)),
+    };
+
+    let key = canon(&file);
+
+    if let Some(start) = self.importing.iter().position(|x| canon(x) == key) {
+      let chain = self.importing[start..].iter().chain([&file]).map(|x| {
+        x.display().to_string()
+      }).collect::<Vec<String>>().join(" -> ");
+
+      return Err(self.error("import cycle", chain, path));
     }
 
-    if kind != value.as_type() {
-      self.error("invalid operation", format!("{:?} has been assigned to be {kind}, not {}", name.text, value.as_type()), name);
-      return;
+    let scope = match self.modules.get(&key) {
+      Some(scope) => scope.clone(),
+      None => {
+        let scope = self.load(file, path)?;
+        self.modules.insert(key, scope.clone()); scope
+      },
+    };
+
+    let name = path.last().unwrap();
+
+    if self.fetch(place).is_some() || outer.as_ref().is_some_and(|x| self.fetch(x).is_some()) {
+      return Err(self.error("symbol already exists", format!("{:?} has already been defined.", name.text), name));
     }
 
-    self.insert(name, Symbol::var(value, true));
+    self.store(place, Symbol::Module { scope });
+    return Ok(());
+  }
+  fn load(&mut self, file: PathBuf, path: &[Token]) -> Result<Rc<RefCell<Scope>>, Error> {
+    let source = match std::fs::read_to_string(&file) {
+      Ok(source) => source,
+      Err(err) => return Err(self.error("invalid path", format!("{} could not be read: {err}", file.display()), path)),
+    };
+
+    let logger = Logger::new(file.display().to_string(), source);
+    let (nodes, logger, errors) = Parser::init(Lexer::new(logger)).parse();
+
+    if !errors.is_empty() {
+      errors.iter().for_each(|x| println!("{x}"));
+      return Err(self.error("invalid module", format!("{} has {} syntax error(s).", file.display(), errors.len()), path));
+    }
+
+    let scope = RootScope();
+    let proto = Compiler::init(Rc::new(*logger), scope.clone()).compile(&nodes);
+    let floor = self.frames.len();
+
+    self.importing.push(file);
+    self.enter(proto.clone(), None, scope.clone(), cells(proto.slots), None);
+
+    let result = self.execute(floor);
+    self.importing.pop();
+
+    return result.map(|_| { self.pop(); scope });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // runs a script, giving back the value of its trailing expression.
+  fn run(source: &str) -> Result<String, String> {
+    let logger = Logger::new("test.baf".into(), source.into());
+    let (nodes, _, errors) = Parser::init(Lexer::new(logger.clone())).parse();
+    assert!(errors.is_empty(), "{errors:?}");
+
+    return Runtime::session(logger.clone()).evaluate(&nodes, logger)
+      .map(|x| x.to_string()).map_err(|x| x.to_string());
+  }
+  // runs each script in turn against the same globals.
+  fn session(sources: &[&str]) -> Vec<Result<String, String>> {
+    let mut runtime = Runtime::session(Logger::new("test.baf".into(), "".into()));
+
+    return sources.iter().map(|source| {
+      let logger = Logger::new("test.baf".into(), source.to_string());
+      let (nodes, _, errors) = Parser::init(Lexer::new(logger.clone())).parse();
+      assert!(errors.is_empty(), "{errors:?}");
+
+      runtime.evaluate(&nodes, logger).map(|x| x.to_string()).map_err(|x| x.to_string())
+    }).collect();
+  }
+
+  #[test]
+  fn unary_operators_negate_and_invert() {
+    assert_eq!(run("-(2 + 3);"), Ok("-5".into()));
+    assert_eq!(run("+4.5;"), Ok("4.5".into()));
+    assert_eq!(run("!(1 > 2);"), Ok("true".into()));
+    assert_eq!(run("-2n;"), Ok("-2".into()));
+    assert!(run("-\"a\";").is_err());
+    assert!(run("-(-9223372036854775808);").is_err());
   }
-  fn import(&mut self, node: Node) {
-    let path = if let Node::ImportLib { path } = node.clone()
-    { path.clone() } else { unreachable!() };
 
-    let path_s = path.iter().map(|x| {
-      x.text.clone()
-    }).collect::<Vec<String>>().join("/") + ".baf";
+  #[test]
+  fn lambdas_are_values() {
+    let source = "set apply = {f: (int) -> int, x: int} -> int { emit f(x); }; set double = {n: int} -> int { emit n * 2; };";
+
+    assert_eq!(run(&format!("{source} apply(double, 4);")), Ok("8".into()));
+    assert_eq!(run(&format!("{source} apply({{n: int}} -> int {{ emit n + 100; }}, 1);")), Ok("101".into()));
+    assert_eq!(run(&format!("{source} [double][0](3);")), Ok("6".into()));
+    assert_eq!(run(&format!("{source} typeof(apply);")), Ok("[((int) -> int, int) -> int]".into()));
+  }
+
+  #[test]
+  fn closures_keep_what_they_capture() {
+    let source = "set adder = {k: int} -> (int) -> int { emit {n: int} -> int { emit n + k; }; }; set add5 = adder(5);";
+
+    assert_eq!(run(&format!("{source} add5(1);")), Ok("6".into()));
+    assert_eq!(run(&format!("{source} adder(2)(3) + add5(0);")), Ok("10".into()));
+  }
+
+  #[test]
+  fn parameters_keep_their_declared_order() {
+    let source = "set f = {z: str, a: int, m: bool} -> str { emit format(\"{} {} {}\", z, a, m); };";
+
+    assert_eq!(run(&format!("{source} f(\"x\", 1, true);")), Ok("x 1 true".into()));
+    assert_eq!(run(&format!("{source} f(m = false, z = \"y\", a = 2);")), Ok("y 2 false".into()));
+    assert_eq!(run(&format!("{source} typeof(f);")), Ok("[(str, int, bool) -> str]".into()));
+  }
 
-    let res = if let Ok(bool) = std::fs::exists(&path_s)
-      { bool } else { false };
+  #[test]
+  fn loops_break_and_continue() {
+    let source = "var total = 0; for x in [1, 2, 3, 4, 5, 6] { if x == 2 { continue; }; if x == 5 { break; }; total = total + x; }";
+    assert_eq!(run(&format!("{source} total;")), Ok("8".into()));
 
-    let res = if !res {
-      if let Ok(bool) = std::fs::exists("lib/".to_string()+&path_s) { bool } else { false }
-    } else { res };
+    let source = "var i = 0; var n = 0; while i < 10 { i = i + 1; if i % 2 == 0 { continue; }; n = n + i; }";
+    assert_eq!(run(&format!("{source} n;")), Ok("25".into()));
+
+    let source = "var n = 0; for a in [1, 2, 3] { for b in [1, 2, 3] { if b == 2 { break; }; n = n + a * b; } }";
+    assert_eq!(run(&format!("{source} n;")), Ok("6".into()));
+  }
+
+  #[test]
+  fn for_goes_over_chars_and_keys() {
+    assert_eq!(run("var s = \"\"; for c in \"abc\" { s = c + s; } s;"), Ok("cba".into()));
+    assert_eq!(run("set o = {b = 1, a = 2}; var s = \"\"; for k in o { s = s + k; } s;"), Ok("ab".into()));
+    assert!(run("for x in 5 { }").is_err());
+  }
+
+  #[test]
+  fn attributes_are_read_and_assigned() {
+    let source = "var o = {a = {b = 1, c = [1, 2]}, n = \"x\"};";
+
+    assert_eq!(run(&format!("{source} o:a:b;")), Ok("1".into()));
+    assert_eq!(run(&format!("{source} o:a:b = 5; o:a:b;")), Ok("5".into()));
+    assert_eq!(run(&format!("{source} o:a:c[1] = 7; o:a:c;")), Ok("[1, 7]".into()));
+    assert!(run(&format!("{source} o:z;")).is_err());
+    assert!(run(&format!("{source} o:n = 1;")).is_err());
+    assert!(run("set o = {a = 1}; o:a = 2;").is_err());
+  }
+
+  #[test]
+  fn structs_are_built_from_their_type() {
+    let source = "type Point { x: int, y: int }; var p = Point { y = 2, x = 1 };";
+
+    assert_eq!(run(&format!("{source} p;")), Ok("Point { x: 1, y: 2 }".into()));
+    assert_eq!(run(&format!("{source} p:x = 10; typeof(p:x, p);")), Ok("[int, Point]".into()));
+    assert!(run(&format!("{source} Point {{ x = 1 }};")).unwrap_err().contains("Point is missing \"y\"."));
+    assert!(run(&format!("{source} Point {{ x = 1, y = 2, z = 3 }};")).unwrap_err().contains("\"z\" is not a field of Point."));
+    assert!(run(&format!("{source} Point {{ x = \"s\", y = 2 }};")).is_err());
+  }
+
+  #[test]
+  fn modules_are_loaded_from_beside_the_script_or_lib() {
+    let dir = std::env::temp_dir().join(format!("baffler-modules-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("lib/geo")).unwrap();
+    std::fs::write(dir.join("lib/geo/shapes.baf"), "set scale = 3; type Vec { x: int }; set grow = {n: int} -> int { emit n * scale; };").unwrap();
+    std::fs::write(dir.join("a.baf"), "use b;").unwrap();
+    std::fs::write(dir.join("b.baf"), "use a;").unwrap();
+
+    let evaluate = |source: &str| {
+      let logger = Logger::new(dir.join("main.baf").display().to_string(), source.into());
+      let (nodes, _, _) = Parser::init(Lexer::new(logger.clone())).parse();
+      Runtime::session(logger.clone()).evaluate(&nodes, logger).map(|x| x.to_string()).map_err(|x| x.to_string())
+    };
+
+    assert_eq!(evaluate("use geo:shapes; shapes:grow(2) + shapes:scale;"), Ok("9".into()));
+    assert_eq!(evaluate("use geo:shapes; shapes:Vec { x = 4 }:x;"), Ok("4".into()));
+    assert!(evaluate("use geo:shapes; shapes:nope;").unwrap_err().contains("could not be resolved in \"shapes\""));
+    assert!(evaluate("use missing;").unwrap_err().contains("missing.baf is not a valid filepath."));
+    assert!(evaluate("use a;").unwrap_err().contains("import cycle"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
 
-    if !res {
-      self.error("invalid path", format!("{path_s} is not a valid filepath."), path.as_slice());
+  #[test]
+  fn errors_halt_with_a_stack_trace() {
+    let source = "set f = {n: int} -> int { set xs = [1, 2]; emit xs[n]; }; set g = {n: int} -> int { emit f(n); };";
+
+    assert_eq!(run(&format!("{source} g(1);")), Ok("2".into()));
+
+    let error = run(&format!("{source} g(5);")).unwrap_err();
+    let lines = error.lines().collect::<Vec<&str>>();
+
+    assert!(lines[0].ends_with("invalid expression"), "{error}");
+    assert!(lines.contains(&"stack trace:"));
+    assert!(lines.iter().position(|x| x.contains("in \"f\"")) < lines.iter().position(|x| x.contains("in \"g\"")));
+  }
+
+  #[test]
+  fn nothing_runs_after_an_error() {
+    let results = session(&["var n = 0;", "n = 1 / 0; n = 5;", "n;"]);
+
+    assert!(results[1].is_err());
+    assert_eq!(results[2], Ok("0".into()));
+  }
+
+  #[test]
+  fn each_iteration_gets_its_own_bindings() {
+    let source = "var fs = [{} -> int { emit 0; }]; for x in [1, 2, 3] { set y = x * 10; fs = fs + {} -> int { emit y; }; }";
+    assert_eq!(run(&format!("{source} fs[1]() + fs[3]();")), Ok("40".into()));
+  }
+
+  #[test]
+  fn calls_recurse_and_fill_in_defaults() {
+    let source = "set fib = {n: int} -> int { if n < 2 { emit n; }; emit fib(n - 1) + fib(n - 2); };";
+    assert_eq!(run(&format!("{source} fib(15);")), Ok("610".into()));
+
+    let source = "set greet = {name: str, greeting: str = \"hello\"} -> str { emit greeting + \" \" + name; };";
+    assert_eq!(run(&format!("{source} greet(\"a\");")), Ok("hello a".into()));
+    assert_eq!(run(&format!("{source} greet(greeting = \"yo\", name = \"b\");")), Ok("yo b".into()));
+  }
+
+  #[test]
+  fn args_without_any_is_an_empty_str_array() {
+    assert_eq!(run("args();"), Ok("[]".into()));
+    assert_eq!(run("set f = {} -> str[] { emit args(); }; f() + \"x\";"), Ok("[x]".into()));
+    assert_eq!(run("args() + [\"y\"];"), Ok("[y]".into()));
+  }
+
+  #[test]
+  fn env_reads_the_environment_or_nothing() {
+    let path = std::env::var("PATH").unwrap_or_default();
+
+    assert_eq!(run("env(\"PATH\");"), Ok(path));
+    assert_eq!(run("env(\"BAFFLER_SURELY_UNSET\");"), Ok("".into()));
+  }
+
+  #[test]
+  fn interpolated_strings_show_each_part() {
+    assert_eq!(run("set n = 2; \"a {n + 1} b {[n]} c {}\";"), Ok("a 3 b [2] c {}".into()));
+    assert_eq!(run("\"<{ \"in {1.5} ner\" }>\";"), Ok("<in 1.5 ner>".into()));
+    assert_eq!(run("\"\\{n}\";"), Ok("{n}".into()));
+  }
+
+  #[test]
+  fn ints_are_checked_for_overflow() {
+    assert_eq!(run("9223372036854775807;"), Ok("9223372036854775807".into()));
+    assert_eq!(run("-9223372036854775808;"), Ok("-9223372036854775808".into()));
+
+    for source in ["9223372036854775807 + 1;", "-9223372036854775808 - 1;", "4611686018427387904 * 2;", "-(-9223372036854775808);"] {
+      let found = run(source).unwrap_err();
+      assert!(found.contains("integer overflow"), "{source}: {found}");
     }
   }
-  
-  fn emit(&mut self, node: Node) -> Value {
-    let expr = if let Node::EmitValue { value } = node 
-      { value } else { unreachable!() };
 
-    return self.evaluate(expr);
+  #[test]
+  fn ints_convert_to_and_from_other_types() {
+    assert_eq!(run("int(\" 42 \");"), Ok("42".into()));
+    assert_eq!(run("int(-2.9);"), Ok("-2".into()));
+    assert_eq!(run("int(12n) + 1;"), Ok("13".into()));
+    assert_eq!(run("num(7) / 2.0;"), Ok("3.5".into()));
+    assert_eq!(run("7 / 2;"), Ok("3".into()));
+
+    assert!(run("int(\"4.2\");").unwrap_err().contains("invalid conversion"));
+    assert!(run("int(9223372036854775808n);").unwrap_err().contains("invalid conversion"));
   }
-  fn expression(&mut self, node: Node) -> Value {
-    let expr = if let Node::Expression { expr } = node 
-    { expr } else { unreachable!() };
 
-    return self.evaluate(expr);
+  #[test]
+  fn bigints_and_decimals_are_exact() {
+    assert_eq!(run("9223372036854775807n * 10n + 1n;"), Ok("92233720368547758071".into()));
+    assert_eq!(run("0.1d + 0.2d;"), Ok("0.3".into()));
+    assert_eq!(run("0.1d + 0.2d == 0.3d;"), Ok("true".into()));
+    assert_eq!(run("19.99d * 3d;"), Ok("59.97".into()));
+    assert_eq!(run("-7n / 2n;"), Ok("-3".into()));
+
+    assert!(run("1n / 0n;").is_err());
+    assert!(run("1d % 0.0d;").is_err());
   }
-  
-  fn create_type(&mut self, node: Node) {
-    let (name, attrs) = if let Node::DeclareType { name, attrs } = node {
-      let attrs = attrs.into_iter().map(|expr| {
-        if let Expr::TypePair { name, kind } = expr {
-          let val = if let Value::TypeRef(t) = self.evaluate(*kind) 
-            { t } else { unreachable!() };
 
-          (name.text, val)
-        } else { unreachable!() }
-      }).collect::<HashMap<String, Type>>();
+  #[test]
+  fn compound_assignments_store_their_result() {
+    assert_eq!(run("var x = 1; x += 5; x *= 2; x -= 3; x /= 2; x %= 3; x;"), Ok("1".into()));
+    assert_eq!(run("var xs = [1, 2]; xs[1] += 10; set s = \"a\"; var o = { s = s }; o:s += \"b\"; format(\"{} {}\", xs, o:s);"), Ok("[1, 12] ab".into()));
+    assert!(run("set x = 1; x += 1;").unwrap_err().contains("is a constant"));
+  }
 
-      (name, attrs)
-    } else { unreachable!() };
+  #[test]
+  fn closures_are_equal_by_what_they_capture() {
+    let source = "set adder = {n: int} -> (int) -> int { emit {x: int} -> int { emit x + n; }; }; set add = adder(1);";
 
-    self.insert(name, Symbol::TypeRefr { parent: Type::Object(attrs) })
+    assert_eq!(run(&format!("{source} adder(1) == adder(2);")), Ok("false".into()));
+    assert_eq!(run(&format!("{source} add == add;")), Ok("true".into()));
+    assert_eq!(run(&format!("{source} set f = add; f == add;")), Ok("true".into()));
   }
-  fn run(&mut self, node: Node) -> Value {
-    let code = if let Node::Compound { value } = node 
-      { value } else { vec![node] };
 
-    let mut kind = Value::NullVoid;
+  #[test]
+  fn mismatched_arguments_are_reported_where_they_are_given() {
+    let found = run("set f = {x: int, y: str} -> int { emit x; };\nf(1,\n 2);");
+    let found = found.map(|_| ()).unwrap_err();
 
-    code.into_iter().for_each(|x| {
-      if let Node::EmitValue { .. } = &x 
-        { kind = self.compute(x); } else 
-        { self.compute(x); }
-    });
+    assert!(found.contains("expected str for \"y\""), "{found}");
+    assert!(found.contains("[3:2]"), "{found}");
+  }
 
-    return kind;
+  #[test]
+  fn empty_array_literals_hold_nothing() {
+    assert_eq!(run("[];"), Ok("[]".into()));
+    assert_eq!(run("var n = 0; for x in [] { n = n + 1; } n;"), Ok("0".into()));
+    assert_eq!(run("[] + 1;"), Ok("[1]".into()));
+  }
+
+  #[test]
+  fn loops_go_over_every_item_even_null() {
+    assert_eq!(run("var n = 0; for x in [disp(\"a\"), disp(\"b\")] { n = n + 1; } n;"), Ok("2".into()));
+  }
+
+  #[test]
+  fn empty_arrays_compare_by_what_they_hold() {
+    assert_eq!(run("args() == [];"), Ok("true".into()));
+    assert_eq!(run("[] > 0;"), Ok("false".into()));
+    assert_eq!(run("[] == [disp(\"\")];"), Ok("false".into()));
+    assert!(run("[disp(\"\")] + 1;").is_err());
   }
-}
 
+  #[test]
+  fn globals_keep_their_slots_across_inputs() {
+    let found = session(&["set f = {} -> int { emit later; };", "f();", "set later = 2;", "f() + later;"]);
+
+    assert!(found[1].as_ref().unwrap_err().contains("\"later\" could not be resolved"));
+    assert_eq!(found[3], Ok("4".into()));
+  }
+
+  #[test]
+  fn failed_inputs_declare_nothing() {
+    let found = session(&["var n = 0;", "set a = 1; n = 1; set x = 1 / 0;", "set a = 2; set x = 3; n + a + x;"]);
+
+    assert!(found[1].is_err());
+    assert_eq!(found[2], Ok("6".into()));
+  }
+
+  #[test]
+  fn emit_gives_an_if_used_as_a_value_its_value() {
+    let source = "set f = {c: bool} -> str { set v = if c { emit 1; } else { emit 2; }; emit format(\"got {}\", v); };";
+    assert_eq!(run(&format!("{source} f(false);")), Ok("got 2".into()));
+
+    let source = "set c = false; set v = if c { emit 1; } else { emit 2; };";
+    assert_eq!(run(&format!("{source} v;")), Ok("2".into()));
+  }
+
+  #[test]
+  fn emit_in_an_if_statement_leaves_the_function() {
+    let source = "set f = {n: int} -> str { if n < 0 { emit \"negative\"; }; emit \"positive\"; };";
+    assert_eq!(run(&format!("{source} f(-1);")), Ok("negative".into()));
+
+    let found = run("var i = 0; while true { i = i + 1; if i == 3 { emit 0; }; }").unwrap_err();
+    assert!(found.contains("can only be used inside of a function"), "{found}");
+    assert_eq!(run("emit disp(\"not shown\"); 1;").map_err(|_| ()), Err(()));
+  }
+
+  #[test]
+  fn strings_are_indexed_by_char() {
+    assert_eq!(run("\"héllo\"[1];"), Ok("é".into()));
+    assert_eq!(run("\"héllo\"[4];"), Ok("o".into()));
+    assert!(run("\"héllo\"[5];").is_err());
+  }
+
+  #[test]
+  fn numbers_of_any_type_are_equal_by_amount() {
+    assert_eq!(run("1 == 1d;"), Ok("true".into()));
+    assert_eq!(run("2n == 2.00d;"), Ok("true".into()));
+    assert_eq!(run("1.5 == 1.5d;"), Ok("true".into()));
+    assert_eq!(run("1 != 1.1d;"), Ok("true".into()));
+    assert_eq!(run("1d / 3d;"), Ok("0.3333333333333333".into()));
+  }
+
+  #[test]
+  fn exit_codes_past_a_byte_are_errors() {
+    for code in ["256", "-1"] {
+      let error = run(&format!("exit({code});")).unwrap_err();
+      assert!(error.contains("exit codes range from 0 to 255"), "{error}");
+    }
+  }
+
+  #[test]
+  fn closures_share_the_bindings_they_capture() {
+    let source = "var n = 0; set bump = {} { n = n + 1; }; bump(); bump();";
+    assert_eq!(run(&format!("{source} n;")), Ok("2".into()));
+
+    let source = "set counter = {} -> () -> int { var n = 0; emit {} -> int { n = n + 1; emit n; }; }; set c = counter(); set d = counter(); c(); c();";
+    assert_eq!(run(&format!("{source} c() * 10 + d();")), Ok("31".into()));
+  }
+
+  #[test]
+  fn changing_a_copy_leaves_the_original_alone() {
+    let source = "var xs = [1, 2, 3]; var ys = xs; var o = { a = xs }; ys[0] = 9; o:a[1] = 8;";
+
+    assert_eq!(run(&format!("{source} xs;")), Ok("[1, 2, 3]".into()));
+    assert_eq!(run(&format!("{source} ys;")), Ok("[9, 2, 3]".into()));
+    assert_eq!(run(&format!("{source} o;")), Ok("{ a: [1, 8, 3] }".into()));
+  }
+
+  #[test]
+  fn a_failed_change_keeps_the_old_value() {
+    let results = session(&["var xs = [1, 2, 3];", "xs[5] = 1;", "xs;"]);
+
+    assert!(results[1].is_err());
+    assert_eq!(results[2], Ok("[1, 2, 3]".into()));
+  }
+
+  #[test]
+  fn empty_arrays_hold_null() {
+    assert!(Value::Array(Rc::new(vec![])).as_type() == Type::Array(Type::NullVoid.wrap()));
+  }
+}
@@ -1,50 +1,77 @@
 use serde::Serialize;
 use std::fmt::Debug;
 
-use crate::token::Token;
+use crate::token::{Span, Token};
 
 type Value = Box<Expr>;
 type Body = Box<Node>;
 
+// every node and expression keeps the span of source it was parsed from, up
+// to but not including a trailing ';'.
 #[derive(Clone, Serialize)]
 pub enum Node {
-  SetAssign { name: Token, value: Expr },
-  VarAssign { name: Token, value: Expr },
-  ChangeVal { name: Token, value: Expr },
+  SetAssign { name: Token, value: Expr, doc: Option<String>, span: Span },
+  VarAssign { name: Token, value: Expr, doc: Option<String>, span: Span },
+  ChangeVal { target: Expr, value: Expr, span: Span },
 
-  ImportLib { path: Vec<Token> },
-  EmitValue { value: Expr },
+  ImportLib { path: Vec<Token>, span: Span },
+  EmitValue { value: Expr, span: Span },
 
-  DeclareType { name: Token, attrs: Vec<Expr> },
+  DeclareType { name: Token, attrs: Vec<Expr>, doc: Option<String>, span: Span },
 
-  Compound { value: Vec<Node> },
-  Expression{ expr: Expr },
+  WhileLoop { cond: Expr, body: Body, span: Span },
+  ForLoop { item: Token, iter: Expr, body: Body, span: Span },
+  Break { token: Token, span: Span },
+  Continue { token: Token, span: Span },
+
+  Compound { value: Vec<Node>, span: Span },
+  Expression { expr: Expr, span: Span },
 }
 
 #[derive(Clone, Serialize)]
 pub enum Expr {
-  String { value: Token },
-  Number { value: Token },
-  Boolean { value: Token },
-  VarRef { value: Token },
-  FunCall { name: Token, args: Vec<Expr> },
-
-  Object { attrs: Vec<Expr> },
-  ObjectField { name: Token, attr: Box<Expr> },
-  Attribute { parent: Value, attr: Value },
-
-  Array { value: Vec<Expr> },
-  Index { parent: Value, index: Value },
-  Lambda { args: Vec<Expr>, kind: Value, body: Body },
-  IfExpr { cond: Value, body: Body, other: Body },
-
-  BoolOper { lhs: Value, oper: Token, rhs: Value },
-  MathOper { lhs: Value, oper: Token, rhs: Value },
-  Chained { lhs: Value, stich: Token, rhs: Value },
-
-  TypeRef { base: Token, arrs: usize, },
-  TypePair { name: Token, kind: Value },
-  NullVoid { prev: Token },
+  String { value: Token, span: Span },
+  Number { value: Token, span: Span },
+  Boolean { value: Token, span: Span },
+  VarRef { value: Token, span: Span },
+  Interpolated { parts: Vec<Expr>, span: Span },
+  FunCall { name: Token, args: Vec<Expr>, span: Span },
+  Call { callee: Value, args: Vec<Expr>, span: Span },
+
+  Object { attrs: Vec<Expr>, span: Span },
+  Construct { space: Vec<Token>, name: Token, attrs: Vec<Expr>, span: Span },
+  ObjectField { name: Token, attr: Box<Expr>, span: Span },
+  Attribute { parent: Value, attr: Value, span: Span },
+
+  Array { value: Vec<Expr>, span: Span },
+  Index { parent: Value, index: Value, span: Span },
+  Lambda { args: Vec<Expr>, kind: Value, body: Body, span: Span },
+  If { cond: Value, body: Body, other: Body, span: Span },
+
+  Unary { oper: Token, value: Value, span: Span },
+  BoolOper { lhs: Value, oper: Token, rhs: Value, span: Span },
+  MathOper { lhs: Value, oper: Token, rhs: Value, span: Span },
+  Chained { lhs: Value, stich: Token, rhs: Value, span: Span },
+
+  TypeRef { space: Vec<Token>, base: Token, arrs: usize, span: Span },
+  FunType { args: Vec<Expr>, kind: Value, span: Span },
+  TypePair { name: Token, kind: Value, default: Option<Value>, span: Span },
+  NullVoid { prev: Token, span: Span },
+}
+
+impl Expr {
+  // flattens `a:b:c` into its names, if the expression is only a path.
+  pub fn path(&self) -> Option<Vec<Token>> {
+    return match self {
+      Expr::VarRef { value, .. } => Some(vec![value.clone()]),
+      Expr::Attribute { parent, attr, .. } => {
+        let mut path = parent.path()?;
+        path.extend(attr.path()?);
+        Some(path)
+      },
+      _ => None,
+    };
+  }
 }
 
 impl Debug for Node {
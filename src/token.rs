@@ -1,22 +1,39 @@
 use serde::Serialize;
 use std::{fmt::{Debug, Display}, hash::Hash};
 
+// a range of bytes in the source, from `start` up to but not including `end`.
+#[derive(Clone, Copy, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Span {
+  pub fn new(start: usize, end: usize) -> Self {
+    return Span { start, end };
+  }
+}
+
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 pub struct Token {
   pub class: Class,
   pub text: String,
-  pub coords: [usize; 2]
+  pub coords: [usize; 2],
+  pub span: Span,
+  // the `##` comments right above a `set`, `var` or `type` keyword.
+  #[serde(skip)]
+  pub doc: Option<String>,
 }
 
 impl Token {
-  pub fn init<S:ToString>(class: Class, text: S, coords: [usize; 2]) -> Self {
-    return Token { class, text: text.to_string(), coords, };
+  pub fn init<S:ToString>(class: Class, text: S, coords: [usize; 2], span: Span) -> Self {
+    return Token { class, text: text.to_string(), coords, span, doc: None };
   }
 }
 
 impl Display for Token {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    write!(f, "token:{} {{\n  text: {:?},\n  coords: {:?}\n}}", self.class, self.text, self.coords)
+    write!(f, "token:{} {{\n  text: {:?},\n  coords: {:?},\n  span: {}..{}\n}}", self.class, self.text, self.coords, self.span.start, self.span.end)
   }
 }
 
@@ -32,6 +49,7 @@ impl Hash for Token {
 pub enum Class {
   Identifier, Keyword,
   Bool, String, Number,
+  StringHead, StringPart, StringTail,
 
   LeftBrace, RightBrace,
   LeftBrack, RightBrack,
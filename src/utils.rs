@@ -1,4 +1,4 @@
-use crate::{syntax::{Expr, Node}, token::{Class, Token}};
+use crate::{syntax::{Expr, Node}, token::{Span, Token}};
 
 pub trait Color {
   fn color(&self, code: u8) -> String;
@@ -9,8 +9,8 @@ impl<T:ToString> Color for T {
   }
 } 
 
-pub trait Coords {
-  fn coords(&self) -> [usize; 3];
+pub trait Spanned {
+  fn span(&self) -> Span;
 }
 
 pub trait Wrapper {
@@ -23,158 +23,91 @@ impl<T> Wrapper for T {
   }
 }
 
-impl Coords for [usize; 2] {
-  fn coords(&self) -> [usize; 3] {
-    [self[0], self[1], 1]
+impl Spanned for Span {
+  fn span(&self) -> Span {
+    *self
+  }
+}
+impl Spanned for &Span {
+  fn span(&self) -> Span {
+    **self
   }
 }
 
-impl Coords for &Token {
-  fn coords(&self) -> [usize; 3] {
-    let offset = if self.class == Class::String { 2 } else { 0 };
-    [self.coords[0], self.coords[1], self.text.len() + offset]
+impl Spanned for &Token {
+  fn span(&self) -> Span {
+    self.span
   }
 }
-impl Coords for &Expr {
-  fn coords(&self) -> [usize; 3] {
+impl Spanned for &Expr {
+  fn span(&self) -> Span {
     match self {
-      Expr::String { value } |
-      Expr::Number { value } |
-      Expr::Boolean { value } |
-      Expr::VarRef { value } => value.coords(),
-      Expr::FunCall { name, args } => {
-        let [line, start, _] = name.coords();
-        let [_, stop, last] = args.last().unwrap().coords();
-
-        [line, start, stop - start + last + 1]
-      },
-      Expr::Array { value } => {
-        if value.len() == 1 {
-          return value[0].coords();
-        }
-
-        let [line, start, _] = value[0].coords();
-        let [_, stop, last] = value.last().unwrap().coords();
-
-        [line, start, stop - start + last]
-      },
-      Expr::Index { parent, index } => {
-        let [line, start, _] = parent.coords();
-        let [_, stop, last] = index.coords();
-
-        [line, start, stop - start + last + 1]
-      },
-      Expr::Lambda { args, kind, .. } => {
-        let [line, start, _] = args[0].coords();
-        let [_, stop, last] = kind.coords();
-
-        [line, start, stop - start + last]
-      },
-      Expr::IfExpr { cond, .. } => cond.coords(),
-
-      Expr::Attribute { parent: lhs, attr: rhs } |
-      Expr::BoolOper { lhs, rhs, .. } |
-      Expr::MathOper { lhs, rhs, .. } |
-      Expr::Chained { lhs, rhs, .. } => {
-        let [line, start, _] = lhs.coords();
-        let [_, stop, last] = rhs.coords();
-
-        [line, start, stop - start + last]
-      },
-      Expr::TypeRef { base, arrs } => {
-        let [line, start, length] = base.coords();
-        [line, start, length + (2 * arrs)]
-      }
-      Expr::ObjectField { name, attr: kind } |
-      Expr::TypePair { name, kind } => {
-        let [line, start, _] = name.coords();
-        let [_, stop, last] = kind.coords();
-
-        [line, start, stop - start + last]
-      },
-      Expr::NullVoid { prev } => {
-        let [line, start, length] = prev.coords();
-        [line, start + length - 1, 1]
-      },
-      Expr::Object { attrs } => {
-        let [line, first, length] = attrs[0].coords();
-        let [second, last, other] = attrs[attrs.len() - 1].coords();
-
-        if line == second {
-          [line, first, last - first + other]
-        } else {
-          [line, first, length]
-        }
-      },
+      Expr::String { span, .. } |
+      Expr::Number { span, .. } |
+      Expr::Boolean { span, .. } |
+      Expr::VarRef { span, .. } |
+      Expr::Interpolated { span, .. } |
+      Expr::FunCall { span, .. } |
+      Expr::Call { span, .. } |
+      Expr::Object { span, .. } |
+      Expr::Construct { span, .. } |
+      Expr::ObjectField { span, .. } |
+      Expr::Attribute { span, .. } |
+      Expr::Array { span, .. } |
+      Expr::Index { span, .. } |
+      Expr::Lambda { span, .. } |
+      Expr::If { span, .. } |
+      Expr::Unary { span, .. } |
+      Expr::BoolOper { span, .. } |
+      Expr::MathOper { span, .. } |
+      Expr::Chained { span, .. } |
+      Expr::TypeRef { span, .. } |
+      Expr::FunType { span, .. } |
+      Expr::TypePair { span, .. } |
+      Expr::NullVoid { span, .. } => *span,
     }
   }
 }
-impl Coords for &Node {
-  fn coords(&self) -> [usize; 3] {
+impl Spanned for &Node {
+  fn span(&self) -> Span {
     match self {
-      Node::SetAssign { name, value } |
-      Node::VarAssign { name, value } => {
-        let [line, start, _] = name.coords();
-        let [_, stop, last] = value.coords();
-
-        [line, start - 4, stop - start + last + 4]
-      },
-      Node::ChangeVal { name, value } => {
-        let [line, start, _] = name.coords();
-        let [_, stop, last] = value.coords();
-
-        [line, start, stop - start + last]
-      },
-      Node::ImportLib { path } => {
-        if path.len() == 1 {
-          return path[0].coords();
-        }
-
-        let [line, start, _] = path[0].coords();
-        let [_, stop, last] = path.last().unwrap().coords();
-
-        [line, start - 4, stop - start + last + 5]
-      },
-      Node::EmitValue { value } => {
-        let [line, start, len] = value.coords();
-
-        [line, start - 5, len + 5]
-      },
-      Node::DeclareType { name, .. } => {
-        let [line, first, len] = name.coords();
-        [line, first - 5, len + 5]
-      },
-      Node::Compound { value } => value.first().unwrap().coords(),
-      Node::Expression { expr } => expr.coords(),
+      Node::SetAssign { span, .. } |
+      Node::VarAssign { span, .. } |
+      Node::ChangeVal { span, .. } |
+      Node::ImportLib { span, .. } |
+      Node::EmitValue { span, .. } |
+      Node::DeclareType { span, .. } |
+      Node::WhileLoop { span, .. } |
+      Node::ForLoop { span, .. } |
+      Node::Break { span, .. } |
+      Node::Continue { span, .. } |
+      Node::Compound { span, .. } |
+      Node::Expression { span, .. } => *span,
     }
   }
 }
 
-impl<T:Coords> Coords for &[T] {
-  fn coords(&self) -> [usize; 3] {
-    if self.len() == 1 {
-      return self[0].coords();
-    }
-
-    let [line, start, _] = self[0].coords();
-    let [_, stop, last] = self.last().unwrap().coords();
+impl<T:Spanned> Spanned for &[T] {
+  fn span(&self) -> Span {
+    let start = self[0].span().start;
+    let end = self.last().unwrap().span().end;
 
-    [line, start, stop - start + last]
+    Span::new(start, end)
   }
 }
 
-impl Coords for Token {
-  fn coords(&self) -> [usize; 3] {
-    return (&self).coords();
+impl Spanned for Token {
+  fn span(&self) -> Span {
+    return (&self).span();
   }
 }
-impl Coords for Expr {
-  fn coords(&self) -> [usize; 3] {
-    return (&self).coords();
+impl Spanned for Expr {
+  fn span(&self) -> Span {
+    return (&self).span();
   }
 }
-impl Coords for Node {
-  fn coords(&self) -> [usize; 3] {
-    return (&self).coords();
+impl Spanned for Node {
+  fn span(&self) -> Span {
+    return (&self).span();
   }
 }